@@ -0,0 +1,343 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # ERC-20 pallet
+//!
+//! The ERC-20 pallet presents Substrate tokens to EVM contracts as ERC-20 tokens, through
+//! precompiles at deterministic addresses:
+//!
+//! - `NativeErc20`, for the currency of `pallet_evm`, at `NATIVE_TOKEN_ADDRESS`.
+//! - `AssetsErc20`, for each asset of `pallet_assets`, at `0xffffffff` followed by the asset
+//!   id as 16 big-endian bytes (see `Module::asset_address`).
+//!
+//! They implement `totalSupply`, `balanceOf`, `transfer`, `approve`, `allowance` and
+//! `transferFrom`, and emit the `Transfer` and `Approval` logs. Tokens are held by the
+//! Substrate accounts the `AddressMapping` gives for Ethereum addresses, and allowances are
+//! kept by this pallet.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{decl_module, decl_storage, traits::{Currency, ExistenceRequirement, UnfilteredDispatchable}};
+use frame_system::RawOrigin;
+use sp_std::{prelude::*, marker::PhantomData, convert::TryFrom};
+use sp_core::{H160, H256, U256};
+use sp_runtime::traits::{StaticLookup, UniqueSaturatedInto, Zero};
+use pallet_evm::{AddressMapping, BalanceOf, Precompiles};
+use evm::{Context, ExitError, ExitSucceed, backend::Log, executor::PrecompileOutput};
+
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+#[cfg(all(feature = "std", test))]
+mod mock;
+
+/// Address of the ERC-20 precompile of the native currency.
+pub const NATIVE_TOKEN_ADDRESS: H160 = H160([
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+	0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00,
+]);
+
+/// Prefix of the addresses of the ERC-20 precompiles of assets.
+pub const ASSET_ADDRESS_PREFIX: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+/// Gas cost of reading a supply, balance or allowance.
+pub const READ_COST: usize = 800;
+
+/// Gas cost of a transfer or an approval.
+pub const WRITE_COST: usize = 20_000;
+
+const SELECTOR_TOTAL_SUPPLY: [u8; 4] = [0x18, 0x16, 0x0d, 0xdd];
+const SELECTOR_BALANCE_OF: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+const SELECTOR_TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+const SELECTOR_ALLOWANCE: [u8; 4] = [0xdd, 0x62, 0xed, 0x3e];
+const SELECTOR_APPROVE: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+const SELECTOR_TRANSFER_FROM: [u8; 4] = [0x23, 0xb8, 0x72, 0xdd];
+
+/// Topic of `Transfer(address,address,uint256)` logs.
+pub const TRANSFER_TOPIC: H256 = H256([
+	0xdd, 0xf2, 0x52, 0xad, 0x1b, 0xe2, 0xc8, 0x9b, 0x69, 0xc2, 0xb0, 0x68, 0xfc, 0x37, 0x8d, 0xaa,
+	0x95, 0x2b, 0xa7, 0xf1, 0x63, 0xc4, 0xa1, 0x16, 0x28, 0xf5, 0x5a, 0x4d, 0xf5, 0x23, 0xb3, 0xef,
+]);
+
+/// Topic of `Approval(address,address,uint256)` logs.
+pub const APPROVAL_TOPIC: H256 = H256([
+	0x8c, 0x5b, 0xe1, 0xe5, 0xeb, 0xec, 0x7d, 0x5b, 0xd1, 0x4f, 0x71, 0x42, 0x7d, 0x1e, 0x84, 0xf3,
+	0xdd, 0x03, 0x14, 0xc0, 0xf7, 0xb2, 0x29, 0x1e, 0x5b, 0x20, 0x0a, 0xc8, 0xc7, 0xc3, 0xb9, 0x25,
+]);
+
+pub trait Trait: pallet_evm::Trait + pallet_assets::Trait {
+	/// Mapping from the Ethereum addresses of token holders to their Substrate accounts.
+	type AddressMapping: AddressMapping<Self::AccountId>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Erc20 {
+		/// Amounts spenders may transfer on behalf of owners, by token address, then owner and
+		/// spender. The maximum amount is never spent.
+		Allowances get(fn allowance):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) (H160, H160) => U256;
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin { }
+}
+
+impl<T: Trait> Module<T> {
+	/// Address of the ERC-20 precompile of an asset.
+	pub fn asset_address(id: T::AssetId) -> H160 {
+		let id: u128 = id.unique_saturated_into();
+		let mut address = [0u8; 20];
+		address[..4].copy_from_slice(&ASSET_ADDRESS_PREFIX);
+		address[4..].copy_from_slice(&id.to_be_bytes());
+		H160(address)
+	}
+
+	/// Asset of an ERC-20 precompile address, if the address is one of an existing asset.
+	pub fn asset_id(address: H160) -> Option<T::AssetId> {
+		if address.as_bytes()[..4] != ASSET_ADDRESS_PREFIX {
+			return None
+		}
+
+		let mut id = [0u8; 16];
+		id.copy_from_slice(&address.as_bytes()[4..]);
+		let id = <T::AssetId as TryFrom<u128>>::try_from(u128::from_be_bytes(id)).ok()?;
+		if id < pallet_assets::Module::<T>::next_asset_id() {
+			Some(id)
+		} else {
+			None
+		}
+	}
+}
+
+/// Token presented as an ERC-20 token.
+trait Token {
+	fn total_supply(&self) -> U256;
+	fn balance_of(&self, holder: H160) -> U256;
+	fn transfer(&self, from: H160, to: H160, amount: U256) -> Result<(), ExitError>;
+}
+
+/// The currency of `pallet_evm`.
+struct Native<T>(PhantomData<T>);
+
+impl<T: Trait> Native<T> {
+	fn amount(amount: U256) -> Result<BalanceOf<T>, ExitError> {
+		u128::try_from(amount).ok()
+			.and_then(|amount| <BalanceOf<T> as TryFrom<u128>>::try_from(amount).ok())
+			.ok_or(ExitError::Other("amount overflow"))
+	}
+}
+
+impl<T: Trait> Token for Native<T> {
+	fn total_supply(&self) -> U256 {
+		let issuance: u128 = T::Currency::total_issuance().unique_saturated_into();
+		U256::from(issuance)
+	}
+
+	fn balance_of(&self, holder: H160) -> U256 {
+		let account_id = T::AddressMapping::into_account_id(holder);
+		let balance: u128 = T::Currency::free_balance(&account_id).unique_saturated_into();
+		U256::from(balance)
+	}
+
+	fn transfer(&self, from: H160, to: H160, amount: U256) -> Result<(), ExitError> {
+		T::Currency::transfer(
+			&T::AddressMapping::into_account_id(from),
+			&T::AddressMapping::into_account_id(to),
+			Self::amount(amount)?,
+			ExistenceRequirement::AllowDeath,
+		).map_err(|_| ExitError::Other("transfer failed"))
+	}
+}
+
+/// An asset of `pallet_assets`.
+struct Asset<T: Trait>(T::AssetId);
+
+impl<T: Trait> Token for Asset<T> {
+	fn total_supply(&self) -> U256 {
+		let supply: u128 = pallet_assets::Module::<T>::total_supply(self.0).unique_saturated_into();
+		U256::from(supply)
+	}
+
+	fn balance_of(&self, holder: H160) -> U256 {
+		let account_id = T::AddressMapping::into_account_id(holder);
+		let balance: u128 = pallet_assets::Module::<T>::balance(self.0, account_id).unique_saturated_into();
+		U256::from(balance)
+	}
+
+	fn transfer(&self, from: H160, to: H160, amount: U256) -> Result<(), ExitError> {
+		let amount = u128::try_from(amount).ok()
+			.and_then(|amount| <<T as pallet_assets::Trait>::Balance as TryFrom<u128>>::try_from(amount).ok())
+			.ok_or(ExitError::Other("amount overflow"))?;
+		// `pallet_assets` refuses transfers of nothing, which ERC-20 allows.
+		if amount.is_zero() {
+			return Ok(())
+		}
+
+		let target = T::Lookup::unlookup(T::AddressMapping::into_account_id(to));
+		pallet_assets::Call::<T>::transfer(self.0, target, amount)
+			.dispatch_bypass_filter(RawOrigin::Signed(T::AddressMapping::into_account_id(from)).into())
+			.map(|_| ())
+			.map_err(|_| ExitError::Other("transfer failed"))
+	}
+}
+
+/// Precompile presenting the currency of `pallet_evm` as an ERC-20 token.
+pub struct NativeErc20<T>(PhantomData<T>);
+
+impl<T: Trait> Precompiles for NativeErc20<T> {
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+		is_static: bool,
+	) -> Option<Result<PrecompileOutput, ExitError>> {
+		if address != NATIVE_TOKEN_ADDRESS {
+			return None
+		}
+
+		Some(execute(address, Native::<T>(PhantomData), input, target_gas, context, is_static))
+	}
+}
+
+/// Precompile presenting each asset of `pallet_assets` as an ERC-20 token.
+pub struct AssetsErc20<T>(PhantomData<T>);
+
+impl<T: Trait> Precompiles for AssetsErc20<T> {
+	fn execute(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<usize>,
+		context: &Context,
+		is_static: bool,
+	) -> Option<Result<PrecompileOutput, ExitError>> {
+		let id = Module::<T>::asset_id(address)?;
+
+		Some(execute(address, Asset::<T>(id), input, target_gas, context, is_static))
+	}
+}
+
+/// Run an ERC-20 call to the precompile of a token at `address`.
+fn execute<K: Token>(
+	address: H160,
+	token: K,
+	input: &[u8],
+	target_gas: Option<usize>,
+	context: &Context,
+	is_static: bool,
+) -> Result<PrecompileOutput, ExitError> {
+	// With a delegate call, the precompile would act for the caller of the delegating contract.
+	if context.address != address {
+		return Err(ExitError::Other("ERC-20 precompiles cannot be delegated to"))
+	}
+	if !context.apparent_value.is_zero() {
+		return Err(ExitError::Other("ERC-20 precompiles take no value"))
+	}
+	if input.len() < 4 {
+		return Err(ExitError::Other("invalid ERC-20 call"))
+	}
+
+	let (selector, args) = input.split_at(4);
+	let caller = context.caller;
+	let is_write = selector == SELECTOR_TRANSFER ||
+		selector == SELECTOR_APPROVE ||
+		selector == SELECTOR_TRANSFER_FROM;
+	let cost = if is_write { WRITE_COST } else { READ_COST };
+	if target_gas.map(|target_gas| cost > target_gas).unwrap_or(false) {
+		return Err(ExitError::OutOfGas)
+	}
+	if is_write && is_static {
+		return Err(ExitError::Other("ERC-20 state changed in a static call"))
+	}
+
+	let mut logs = Vec::new();
+	let output = if selector == SELECTOR_TOTAL_SUPPLY {
+		encode_amount(token.total_supply())
+	} else if selector == SELECTOR_BALANCE_OF {
+		encode_amount(token.balance_of(address_arg(args, 0)?))
+	} else if selector == SELECTOR_ALLOWANCE {
+		encode_amount(Allowances::get(address, (address_arg(args, 0)?, address_arg(args, 1)?)))
+	} else if selector == SELECTOR_TRANSFER {
+		let to = address_arg(args, 0)?;
+		let amount = amount_arg(args, 1)?;
+		token.transfer(caller, to, amount)?;
+		logs.push(log(address, TRANSFER_TOPIC, caller, to, amount));
+		encode_true()
+	} else if selector == SELECTOR_APPROVE {
+		let spender = address_arg(args, 0)?;
+		let amount = amount_arg(args, 1)?;
+		Allowances::insert(address, (caller, spender), amount);
+		logs.push(log(address, APPROVAL_TOPIC, caller, spender, amount));
+		encode_true()
+	} else if selector == SELECTOR_TRANSFER_FROM {
+		let from = address_arg(args, 0)?;
+		let to = address_arg(args, 1)?;
+		let amount = amount_arg(args, 2)?;
+		if caller != from {
+			let allowance = Allowances::get(address, (from, caller));
+			if allowance < amount {
+				return Err(ExitError::Other("ERC-20 allowance exceeded"))
+			}
+			if allowance != U256::max_value() {
+				Allowances::insert(address, (from, caller), allowance - amount);
+			}
+		}
+		token.transfer(from, to, amount)?;
+		logs.push(log(address, TRANSFER_TOPIC, from, to, amount));
+		encode_true()
+	} else {
+		return Err(ExitError::Other("unknown ERC-20 function"))
+	};
+
+	Ok(PrecompileOutput {
+		exit_status: ExitSucceed::Returned,
+		cost,
+		output,
+		logs,
+	})
+}
+
+fn arg(args: &[u8], index: usize) -> Result<&[u8], ExitError> {
+	args.get(index * 32..(index + 1) * 32).ok_or(ExitError::Other("invalid ERC-20 call"))
+}
+
+fn address_arg(args: &[u8], index: usize) -> Result<H160, ExitError> {
+	Ok(H160::from_slice(&arg(args, index)?[12..]))
+}
+
+fn amount_arg(args: &[u8], index: usize) -> Result<U256, ExitError> {
+	Ok(U256::from_big_endian(arg(args, index)?))
+}
+
+fn encode_amount(amount: U256) -> Vec<u8> {
+	let mut output = vec![0u8; 32];
+	amount.to_big_endian(&mut output);
+	output
+}
+
+fn encode_true() -> Vec<u8> {
+	encode_amount(U256::one())
+}
+
+fn log(address: H160, topic: H256, from: H160, to: H160, amount: U256) -> Log {
+	Log {
+		address,
+		topics: vec![topic, H256::from(from), H256::from(to)],
+		data: encode_amount(amount),
+	}
+}
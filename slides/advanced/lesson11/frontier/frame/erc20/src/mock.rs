@@ -0,0 +1,188 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+use super::*;
+use crate::{Module, Trait};
+use std::collections::BTreeMap;
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use pallet_evm::{GenesisAccount, HashTruncateConvertAccountId, HashedAddressMapping};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	ModuleId, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = H256;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 6000 / 2;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+	pub BlockGasLimit: U256 = U256::from(15_000_000);
+	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
+}
+
+impl pallet_evm::Trait for Test {
+	type ModuleId = EVMModuleId;
+	type FeeCalculator = ();
+	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = ();
+	type Precompiles = (NativeErc20<Test>, AssetsErc20<Test>);
+	type ChainId = ChainId;
+	type BlockGasLimit = BlockGasLimit;
+	type FindAuthor = ();
+	type OnChargeTransaction = ();
+	type FeePayer = ();
+	type AccessControl = ();
+	type EmergencyOrigin = frame_system::EnsureRoot<H256>;
+	type WeightInfo = ();
+}
+
+impl pallet_assets::Trait for Test {
+	type Event = ();
+	type Balance = u64;
+	type AssetId = u32;
+}
+
+impl Trait for Test {
+	type AddressMapping = HashedAddressMapping<BlakeTwo256>;
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Assets = pallet_assets::Module<Test>;
+pub type Evm = pallet_evm::Module<Test>;
+pub type Erc20 = Module<Test>;
+
+/// Ethereum addresses of token holders.
+pub fn alice() -> H160 { H160::repeat_byte(0xaa) }
+pub fn bob() -> H160 { H160::repeat_byte(0xbb) }
+pub fn charlie() -> H160 { H160::repeat_byte(0xcc) }
+
+/// Contract calling the native token precompile with its own call data, then reverting or
+/// stopping, at `forwarder(revert)`.
+pub fn forwarder(revert: bool) -> H160 {
+	H160::from_low_u64_be(if revert { 0xf1 } else { 0xf0 })
+}
+
+fn forwarder_code(revert: bool) -> Vec<u8> {
+	// CALLDATACOPY the call data, then CALL the precompile with it.
+	let mut code = vec![0x36, 0x60, 0x00, 0x60, 0x00, 0x37];
+	code.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0x36, 0x60, 0x00, 0x60, 0x00, 0x73]);
+	code.extend_from_slice(NATIVE_TOKEN_ADDRESS.as_bytes());
+	code.extend_from_slice(&[0x5a, 0xf1, 0x50]);
+	if revert {
+		code.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xfd]);
+	} else {
+		code.push(0x00);
+	}
+	code
+}
+
+/// Substrate account holding the tokens of an Ethereum address.
+pub fn account(address: H160) -> H256 {
+	<HashedAddressMapping<BlakeTwo256> as AddressMapping<H256>>::into_account_id(address)
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![
+			(account(alice()), 1_000),
+			(account(forwarder(false)), 1_000),
+			(account(forwarder(true)), 1_000),
+		],
+	}.assimilate_storage(&mut t).unwrap();
+
+	let mut accounts = BTreeMap::new();
+	for revert in &[false, true] {
+		accounts.insert(forwarder(*revert), GenesisAccount {
+			nonce: U256::one(),
+			balance: U256::zero(),
+			storage: Default::default(),
+			code: forwarder_code(*revert),
+		});
+	}
+	pallet_evm::GenesisConfig { accounts, frozen: Default::default() }.assimilate_storage(&mut t).unwrap();
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,131 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! ERC-20 pallet tests
+
+use super::*;
+use mock::*;
+use frame_support::{assert_ok, traits::UnfilteredDispatchable};
+use pallet_evm::{ExecutionInfo, ExitReason, ExitRevert};
+
+fn call(caller: H160, token: H160, selector: [u8; 4], args: &[U256]) -> ExecutionInfo<Vec<u8>> {
+	let mut input = selector.to_vec();
+	for arg in args {
+		input.extend_from_slice(&encode_amount(*arg));
+	}
+	Evm::execute_call(caller, token, input, U256::zero(), 1_000_000, U256::zero(), None, true).unwrap()
+}
+
+fn address(address: H160) -> U256 {
+	U256::from_big_endian(H256::from(address).as_bytes())
+}
+
+fn returned(info: &ExecutionInfo<Vec<u8>>) -> U256 {
+	assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+	U256::from_big_endian(&info.value)
+}
+
+#[test]
+fn native_currency_is_an_erc20_token() {
+	new_test_ext().execute_with(|| {
+		let token = NATIVE_TOKEN_ADDRESS;
+
+		assert_eq!(returned(&call(bob(), token, SELECTOR_TOTAL_SUPPLY, &[])), U256::from(3_000));
+		assert_eq!(returned(&call(bob(), token, SELECTOR_BALANCE_OF, &[address(alice())])), U256::from(1_000));
+
+		let info = call(alice(), token, SELECTOR_TRANSFER, &[address(bob()), U256::from(300)]);
+		assert_eq!(returned(&info), U256::one());
+		assert_eq!(info.logs.len(), 1);
+		assert_eq!(info.logs[0].address, token);
+		assert_eq!(info.logs[0].topics, vec![TRANSFER_TOPIC, H256::from(alice()), H256::from(bob())]);
+		assert_eq!(info.logs[0].data, encode_amount(U256::from(300)));
+		assert_eq!(Balances::free_balance(account(alice())), 700);
+		assert_eq!(Balances::free_balance(account(bob())), 300);
+
+		// Failed transfers revert.
+		let info = call(bob(), token, SELECTOR_TRANSFER, &[address(alice()), U256::from(301)]);
+		assert!(matches!(info.exit_reason, ExitReason::Error(_)));
+		assert_eq!(Balances::free_balance(account(bob())), 300);
+	});
+}
+
+#[test]
+fn allowances_limit_transfers_from() {
+	new_test_ext().execute_with(|| {
+		let token = NATIVE_TOKEN_ADDRESS;
+
+		let info = call(alice(), token, SELECTOR_APPROVE, &[address(bob()), U256::from(100)]);
+		assert_eq!(returned(&info), U256::one());
+		assert_eq!(info.logs[0].topics, vec![APPROVAL_TOPIC, H256::from(alice()), H256::from(bob())]);
+		assert_eq!(
+			returned(&call(charlie(), token, SELECTOR_ALLOWANCE, &[address(alice()), address(bob())])),
+			U256::from(100),
+		);
+
+		let transfer_from = |amount: u64| call(
+			bob(),
+			token,
+			SELECTOR_TRANSFER_FROM,
+			&[address(alice()), address(charlie()), U256::from(amount)],
+		);
+		assert!(matches!(transfer_from(150).exit_reason, ExitReason::Error(_)));
+		assert_eq!(returned(&transfer_from(60)), U256::one());
+		assert_eq!(Balances::free_balance(account(charlie())), 60);
+		assert_eq!(Erc20::allowance(token, (alice(), bob())), U256::from(40));
+
+		// The maximum allowance is never spent.
+		call(alice(), token, SELECTOR_APPROVE, &[address(bob()), U256::max_value()]);
+		assert_eq!(returned(&transfer_from(60)), U256::one());
+		assert_eq!(Erc20::allowance(token, (alice(), bob())), U256::max_value());
+	});
+}
+
+#[test]
+fn assets_are_erc20_tokens_at_derived_addresses() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(pallet_assets::Call::<Test>::issue(500).dispatch_bypass_filter(Origin::signed(account(alice()))));
+		let token = Erc20::asset_address(0);
+		assert_eq!(Erc20::asset_id(token), Some(0));
+		assert_eq!(Erc20::asset_id(Erc20::asset_address(1)), None);
+
+		assert_eq!(returned(&call(bob(), token, SELECTOR_TOTAL_SUPPLY, &[])), U256::from(500));
+		assert_eq!(returned(&call(alice(), token, SELECTOR_TRANSFER, &[address(bob()), U256::from(200)])), U256::one());
+		assert_eq!(returned(&call(alice(), token, SELECTOR_TRANSFER, &[address(bob()), U256::zero()])), U256::one());
+		assert_eq!(Assets::balance(0, account(alice())), 300);
+		assert_eq!(returned(&call(alice(), token, SELECTOR_BALANCE_OF, &[address(bob())])), U256::from(200));
+
+		// Assets which were not issued have no precompile.
+		let info = call(alice(), Erc20::asset_address(1), SELECTOR_TOTAL_SUPPLY, &[]);
+		assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+	});
+}
+
+#[test]
+fn transfers_revert_with_their_caller() {
+	new_test_ext().execute_with(|| {
+		let args = [address(bob()), U256::from(100)];
+
+		let info = call(alice(), forwarder(true), SELECTOR_TRANSFER, &args);
+		assert_eq!(info.exit_reason, ExitReason::Revert(ExitRevert::Reverted));
+		assert_eq!(Balances::free_balance(account(forwarder(true))), 1_000);
+		assert_eq!(Balances::free_balance(account(bob())), 0);
+
+		let info = call(alice(), forwarder(false), SELECTOR_TRANSFER, &args);
+		assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+		assert_eq!(Balances::free_balance(account(forwarder(false))), 900);
+		assert_eq!(Balances::free_balance(account(bob())), 100);
+	});
+}
@@ -0,0 +1,188 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Ethereum pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, account};
+use frame_support::traits::{OnFinalize, UnfilteredDispatchable, Currency};
+use sp_runtime::traits::UniqueSaturatedFrom;
+use ethereum::{TransactionAction, TransactionSignature};
+use pallet_evm::ConvertAccountId;
+
+const SEED: u32 = 0;
+/// Maximum gas limit of the benchmarked transactions. Weights are linear in gas above it.
+const MAX_GAS: u32 = 3_000_000;
+/// Maximum number of transactions in a block.
+const MAX_TRANSACTIONS: u32 = 1_000;
+/// Maximum number of logs emitted by all the transactions of a block.
+const MAX_LOGS: u32 = 5_000;
+/// Init code deploying `JUMPDEST PUSH1 0x00 JUMP`, which loops until it runs out of gas.
+const LOOP_INIT_CODE: [u8; 13] = [0x63, 0x5b, 0x60, 0x00, 0x56, 0x60, 0x00, 0x52, 0x60, 0x04, 0x60, 0x1c, 0xf3];
+
+fn keccak(data: &[u8]) -> H256 {
+	H256::from_slice(Keccak256::digest(data).as_slice())
+}
+
+/// Unsigned transaction, with a placeholder signature.
+fn transaction(nonce: u32, action: TransactionAction, gas_limit: u32, gas_price: U256) -> Transaction {
+	Transaction {
+		nonce: nonce.into(),
+		gas_price,
+		gas_limit: gas_limit.into(),
+		action,
+		value: U256::zero(),
+		signature: TransactionSignature::new(27, H256::repeat_byte(1), H256::repeat_byte(1))
+			.expect("the placeholder signature is in range; qed"),
+		input: Vec::new(),
+	}
+}
+
+/// Sign a transaction for the chain id of the runtime, returning the address of its signer.
+fn sign<T: Trait>(mut transaction: Transaction) -> (H160, Transaction) {
+	let secret = secp256k1::SecretKey::parse(&[1u8; 32]).expect("the key is in range; qed");
+	let public = secp256k1::PublicKey::from_secret_key(&secret);
+	let address = H160::from(keccak(&public.serialize()[1..]));
+
+	let chain_id = T::ChainId::get();
+	let hash = transaction.message_hash(Some(chain_id));
+	let (signature, recovery_id) = secp256k1::sign(
+		&secp256k1::Message::parse(hash.as_fixed_bytes()),
+		&secret,
+	);
+	let signature = signature.serialize();
+	transaction.signature = TransactionSignature::new(
+		recovery_id.serialize() as u64 + chain_id * 2 + 35,
+		H256::from_slice(&signature[0..32]),
+		H256::from_slice(&signature[32..64]),
+	).expect("secp256k1 signatures are in range; qed");
+
+	(address, transaction)
+}
+
+/// EVM address of an account, funded through a deposit to pay for `gas` at the minimum gas
+/// price, along with that gas price.
+fn funded<T: Trait>(gas: u32) -> Result<(H160, U256), &'static str> {
+	let caller: T::AccountId = account("caller", 0, SEED);
+	let gas_price = <T as pallet_evm::Trait>::FeeCalculator::min_gas_price();
+	let value = pallet_evm::BalanceOf::<T>::unique_saturated_from(
+		gas_price.saturating_mul(U256::from(gas)).low_u128()
+	);
+	<T as pallet_evm::Trait>::Currency::make_free_balance_be(&caller, value + value);
+	pallet_evm::Call::<T>::deposit_balance(value)
+		.dispatch_bypass_filter(frame_system::RawOrigin::Signed(caller.clone()).into())?;
+
+	Ok((T::ConvertAccountId::convert_account_id(&caller), gas_price))
+}
+
+benchmarks! {
+	where_clause { where <T as frame_system::Trait>::Origin: From<RawOrigin> }
+
+	_ { }
+
+	// Recovery of the sender when a transaction is checked as a self-contained call.
+	recover_signer {
+		let (address, transaction) = sign::<T>(transaction(0, TransactionAction::Create, 21_000, U256::zero()));
+	}: { Module::<T>::recover_signer(&transaction) }
+	verify {
+		assert_eq!(Module::<T>::recover_signer(&transaction), Some(address));
+	}
+
+	// The called contract loops until it runs out of gas, so that the weight of a transaction
+	// is that of executing its gas.
+	transact {
+		let g in 21_000 .. MAX_GAS;
+
+		let (source, gas_price) = funded::<T>(g)?;
+		let target = pallet_evm::Module::<T>::execute_create(
+			source,
+			LOOP_INIT_CODE.to_vec(),
+			U256::zero(),
+			100_000,
+			U256::zero(),
+			None,
+			true,
+		).map_err(|_| "Failed to deploy the looping contract")?.value;
+		let (_, transaction) = sign::<T>(transaction(1, TransactionAction::Call(target), g, gas_price));
+	}: _(RawOrigin::EthereumTransaction(source), transaction)
+	verify {
+		assert_eq!(pallet_evm::Module::<T>::accounts(&source).nonce, U256::from(2));
+	}
+
+	// Each transaction emits `l / t` logs, of four topics and one word of data. No EVM account
+	// changes: committing them to the state trie is benchmarked by the `commit_state` benchmark
+	// of `pallet_evm`, and charged as they change.
+	on_finalize {
+		let t in 0 .. MAX_TRANSACTIONS;
+		let l in 0 .. MAX_LOGS;
+
+		for i in 0 .. t {
+			let transaction = transaction(i, TransactionAction::Call(H160::zero()), 21_000, U256::zero());
+			let transaction_hash = keccak(&rlp::encode(&transaction));
+			let logs = (0 .. l / t + (i < l % t) as u32).map(|_| Log {
+				address: H160::zero(),
+				topics: vec![H256::zero(); 4],
+				data: vec![0; 32],
+			}).collect::<Vec<_>>();
+			let logs_bloom = ethereum::logs_bloom(&logs);
+
+			TransactionStatuses::insert(transaction_hash, TransactionStatus {
+				transaction_hash,
+				transaction_index: i,
+				from: H160::zero(),
+				to: Some(H160::zero()),
+				contract_address: None,
+				logs: logs.clone(),
+				logs_bloom,
+				sponsor: None,
+			});
+			PendingTransactionsAndReceipts::append((transaction, ethereum::Receipt {
+				state_root: H256::default(),
+				used_gas: U256::default(),
+				logs_bloom,
+				logs,
+			}));
+		}
+		let n = frame_system::Module::<T>::block_number();
+	}: { Module::<T>::on_finalize(n) }
+	verify {
+		assert!(BlockNumbers::<T>::contains_key(n));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn transact() {
+		new_test_ext(0).1.execute_with(|| {
+			assert_ok!(test_benchmark_transact::<Test>());
+		});
+	}
+
+	#[test]
+	fn on_finalize() {
+		new_test_ext(0).1.execute_with(|| {
+			assert_ok!(test_benchmark_on_finalize::<Test>());
+		});
+	}
+}
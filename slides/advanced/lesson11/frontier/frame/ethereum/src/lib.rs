@@ -24,16 +24,19 @@
 
 use frame_support::{
 	decl_module, decl_storage, decl_error, decl_event, ensure,
-	weights::Weight, traits::Get, traits::FindAuthor
+	weights::Weight, traits::Get, dispatch::DispatchResult,
 };
 use sp_std::prelude::*;
-use frame_system::ensure_none;
 use ethereum_types::{H160, H64, H256, U256, Bloom};
 use sp_runtime::{
 	traits::UniqueSaturatedInto,
-	transaction_validity::{TransactionValidity, TransactionSource, ValidTransaction}
+	transaction_validity::{
+		TransactionValidity, TransactionValidityError, InvalidTransaction, ValidTransaction,
+	},
 };
+use pallet_evm::FeeCalculator;
 use rlp;
+use codec::{Encode, Decode};
 use sha3::{Digest, Keccak256};
 
 pub use frontier_rpc_primitives::TransactionStatus;
@@ -45,6 +48,8 @@ mod tests;
 #[cfg(all(feature = "std", test))]
 mod mock;
 
+mod benchmarking;
+
 /// A type alias for the balance type from this pallet's point of view.
 pub type BalanceOf<T> = <T as pallet_balances::Trait>::Balance;
 
@@ -55,8 +60,193 @@ pub type BalanceOf<T> = <T as pallet_balances::Trait>::Balance;
 /// `frame_system::Trait` should always be included in our implied traits.
 pub trait Trait: frame_system::Trait<Hash=H256> + pallet_balances::Trait + pallet_timestamp::Trait + pallet_evm::Trait {
 	/// The overarching event type.
-	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
-	type FindAuthor: FindAuthor<H160>;
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+	/// The overarching origin type, from which Ethereum transaction origins are taken. This is
+	/// usually the same type as `frame_system::Trait::Origin`.
+	type RuntimeOrigin: From<<Self as frame_system::Trait>::Origin>
+		+ Into<Result<RawOrigin, Self::RuntimeOrigin>>;
+	/// Weight information for the extrinsics and hooks of this module.
+	type WeightInfo: WeightInfo;
+}
+
+/// Weight functions of the extrinsics and hooks of this module.
+pub trait WeightInfo {
+	fn recover_signer() -> Weight;
+	fn transact(g: u32, ) -> Weight;
+	fn on_finalize(t: u32, l: u32, ) -> Weight;
+}
+
+impl WeightInfo for () {
+	fn recover_signer() -> Weight { 1_000_000_000 }
+	fn transact(g: u32, ) -> Weight { 1_000_000_000u64.saturating_add((g as Weight).saturating_mul(100_000)) }
+	fn on_finalize(t: u32, l: u32, ) -> Weight {
+		1_000_000_000u64
+			.saturating_add((t as Weight).saturating_mul(10_000_000))
+			.saturating_add((l as Weight).saturating_mul(1_000_000))
+	}
+}
+
+/// Minimum gas cost of a log, that of a `LOG0` without data. It bounds the number of logs a
+/// transaction can emit.
+pub const LOG_GAS: u32 = 375;
+
+/// Chain id used when the genesis config has no `ethereum` section.
+pub const DEFAULT_CHAIN_ID: u64 = 42;
+
+/// Block gas limit used when the genesis config has no `ethereum` section.
+pub const DEFAULT_BLOCK_GAS_LIMIT: u32 = u32::max_value();
+
+/// Origin for the Ethereum pallet.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum RawOrigin {
+	/// The call is an Ethereum transaction signed by the address.
+	EthereumTransaction(H160),
+}
+
+/// Origin for the Ethereum pallet.
+pub type Origin = RawOrigin;
+
+/// Ensure the origin is an Ethereum transaction, returning its sender.
+pub fn ensure_ethereum_transaction<OuterOrigin>(o: OuterOrigin) -> Result<H160, &'static str>
+	where OuterOrigin: Into<Result<RawOrigin, OuterOrigin>>
+{
+	match o.into() {
+		Ok(RawOrigin::EthereumTransaction(source)) => Ok(source),
+		_ => Err("bad origin: expected to be an Ethereum transaction"),
+	}
+}
+
+/// Why the execution of a transaction stopped, as reported by `pallet_evm`.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum ExitReason {
+	/// Execution succeeded.
+	Succeed(ExitSucceed),
+	/// Execution failed with a normal EVM error.
+	Error(ExitError),
+	/// Execution was reverted.
+	Revert(ExitRevert),
+	/// Execution failed with an error not supposed to happen in the EVM.
+	Fatal(ExitFatal),
+}
+
+/// Why the execution of a transaction succeeded.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum ExitSucceed {
+	Stopped,
+	Returned,
+	Suicided,
+}
+
+/// Why the execution of a transaction was reverted.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum ExitRevert {
+	Reverted,
+}
+
+/// Why the execution of a transaction failed.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum ExitError {
+	StackUnderflow,
+	StackOverflow,
+	InvalidJump,
+	InvalidRange,
+	DesignatedInvalid,
+	CallTooDeep,
+	CreateCollision,
+	CreateContractLimit,
+	MemoryLimit,
+	StorageReadLimit,
+	StorageWriteLimit,
+	OutOfOffset,
+	OutOfGas,
+	OutOfFund,
+	PCUnderflow,
+	CreateEmpty,
+	/// Other error, with its description.
+	Other(Vec<u8>),
+	/// The access control of the EVM does not allow the call.
+	CallNotAllowed,
+	/// The access control of the EVM does not allow the creation.
+	CreateNotAllowed,
+}
+
+/// Why the execution of a transaction failed with an error not supposed to happen in the EVM.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, sp_runtime::RuntimeDebug)]
+pub enum ExitFatal {
+	NotSupported,
+	UnhandledInterrupt,
+	CallErrorAsFatal(ExitError),
+	/// Other error, with its description.
+	Other(Vec<u8>),
+}
+
+impl From<pallet_evm::ExitReason> for ExitReason {
+	fn from(reason: pallet_evm::ExitReason) -> Self {
+		match reason {
+			pallet_evm::ExitReason::Succeed(s) => Self::Succeed(s.into()),
+			pallet_evm::ExitReason::Error(e) => Self::Error(e.into()),
+			pallet_evm::ExitReason::Revert(r) => Self::Revert(r.into()),
+			pallet_evm::ExitReason::Fatal(f) => Self::Fatal(f.into()),
+		}
+	}
+}
+
+impl From<pallet_evm::ExitSucceed> for ExitSucceed {
+	fn from(succeed: pallet_evm::ExitSucceed) -> Self {
+		match succeed {
+			pallet_evm::ExitSucceed::Stopped => Self::Stopped,
+			pallet_evm::ExitSucceed::Returned => Self::Returned,
+			pallet_evm::ExitSucceed::Suicided => Self::Suicided,
+		}
+	}
+}
+
+impl From<pallet_evm::ExitRevert> for ExitRevert {
+	fn from(revert: pallet_evm::ExitRevert) -> Self {
+		match revert {
+			pallet_evm::ExitRevert::Reverted => Self::Reverted,
+		}
+	}
+}
+
+impl From<pallet_evm::ExitError> for ExitError {
+	fn from(error: pallet_evm::ExitError) -> Self {
+		use pallet_evm::ExitError as E;
+
+		match error {
+			E::StackUnderflow => Self::StackUnderflow,
+			E::StackOverflow => Self::StackOverflow,
+			E::InvalidJump => Self::InvalidJump,
+			E::InvalidRange => Self::InvalidRange,
+			E::DesignatedInvalid => Self::DesignatedInvalid,
+			E::CallTooDeep => Self::CallTooDeep,
+			E::CreateCollision => Self::CreateCollision,
+			E::CreateContractLimit => Self::CreateContractLimit,
+			E::MemoryLimit => Self::MemoryLimit,
+			E::StorageReadLimit => Self::StorageReadLimit,
+			E::StorageWriteLimit => Self::StorageWriteLimit,
+			E::OutOfOffset => Self::OutOfOffset,
+			E::OutOfGas => Self::OutOfGas,
+			E::OutOfFund => Self::OutOfFund,
+			E::PCUnderflow => Self::PCUnderflow,
+			E::CreateEmpty => Self::CreateEmpty,
+			E::Other(description) => Self::Other(description.as_bytes().to_vec()),
+			E::CallNotAllowed => Self::CallNotAllowed,
+			E::CreateNotAllowed => Self::CreateNotAllowed,
+		}
+	}
+}
+
+impl From<pallet_evm::ExitFatal> for ExitFatal {
+	fn from(fatal: pallet_evm::ExitFatal) -> Self {
+		match fatal {
+			pallet_evm::ExitFatal::NotSupported => Self::NotSupported,
+			pallet_evm::ExitFatal::UnhandledInterrupt => Self::UnhandledInterrupt,
+			pallet_evm::ExitFatal::CallErrorAsFatal(e) => Self::CallErrorAsFatal(e.into()),
+			pallet_evm::ExitFatal::Other(description) =>
+				Self::Other(description.as_bytes().to_vec()),
+		}
+	}
 }
 
 decl_storage! {
@@ -73,6 +263,18 @@ decl_storage! {
 		PendingTransactionsAndReceipts: Vec<(ethereum::Transaction, ethereum::Receipt)>;
 		TransactionStatuses: map hasher(blake2_128_concat) H256 => Option<TransactionStatus>;
 		Transactions: map hasher(blake2_128_concat) H256 => Option<(H256, u32)>;
+
+		/// Ethereum chain id set at genesis, for runtimes to use as `pallet_evm::Trait::ChainId`.
+		ChainId get(fn chain_id) config(): u64 = DEFAULT_CHAIN_ID;
+		/// Gas limit reported in Ethereum block headers.
+		BlockGasLimit get(fn block_gas_limit) config(): U256 = U256::from(DEFAULT_BLOCK_GAS_LIMIT);
+	}
+	add_extra_genesis {
+		build(|config: &GenesisConfig| {
+			// A zero chain id or gas limit makes every transaction invalid.
+			assert!(config.chain_id != 0, "Ethereum chain id must not be zero");
+			assert!(!config.block_gas_limit.is_zero(), "Ethereum block gas limit must not be zero");
+		});
 	}
 }
 
@@ -80,10 +282,10 @@ decl_event!(
 	/// Events are a simple means of reporting specific conditions and
 	/// circumstances that have happened that users, Dapps and/or chain explorers would find
 	/// interesting and otherwise difficult to detect.
-	pub enum Event<T> where B = <T as pallet_balances::Trait>::Balance {
-		// Just a normal `enum`, here's a dummy event to ensure it compiles.
-		/// Dummy event, just here so there's a generic type that's used.
-		Dummy(B),
+	pub enum Event {
+		/// An Ethereum transaction was executed: sender, target or created contract address,
+		/// transaction hash and exit reason.
+		Executed(H160, H160, H256, ExitReason),
 	}
 );
 
@@ -127,6 +329,8 @@ decl_error! {
 	pub enum Error for Module<T: Trait> {
 		/// Transaction signed with wrong chain id
 		InvalidChainId,
+		/// Gas limit of the transaction does not fit the EVM gas limit
+		GasLimitTooHigh,
 	}
 }
 
@@ -139,37 +343,27 @@ decl_module! {
 		/// looks like: `fn deposit_event() = default;`.
 		fn deposit_event() = default;
 
-		/// Transact an Ethereum transaction.
-		#[weight = 0]
+		/// Transact an Ethereum transaction, from the sender recovered when checking it as a
+		/// self-contained call.
+		#[weight = Module::<T>::transaction_weight(saturated_gas_limit(transaction.gas_limit))]
 		fn transact(origin, transaction: ethereum::Transaction) {
-			ensure_none(origin)?;
+			let source = ensure_ethereum_transaction(T::RuntimeOrigin::from(origin))?;
 
 			ensure!(
 				transaction.signature.chain_id().unwrap_or_default() == T::ChainId::get(),
 				Error::<T>::InvalidChainId
 			);
-			let mut sig = [0u8; 65];
-			let mut msg = [0u8; 32];
-			sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
-			sig[32..64].copy_from_slice(&transaction.signature.s()[..]);
-			sig[64] = transaction.signature.standard_v();
-			msg.copy_from_slice(&transaction.message_hash(Some(T::ChainId::get()))[..]);
-
-			let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg)
-				.map_err(|_| "Recover public key failed")?;
-			let source = H160::from(H256::from_slice(Keccak256::digest(&pubkey).as_slice()));
-
-			Self::execute(source, transaction);
+
+			Self::execute(source, transaction)?;
 		}
 
 		// The signature could also look like: `fn on_initialize()`.
 		// This function could also very well have a weight annotation, similar to any other. The
 		// only difference is that it mut be returned, not annotated.
 		fn on_initialize(_n: T::BlockNumber) -> Weight {
-			// Anything that needs to be done at the start of the block.
-			// We don't do anything here.
-
-			0
+			// Building the Ethereum block in `on_finalize` costs this, plus a share charged to
+			// each transaction.
+			<T as Trait>::WeightInfo::on_finalize(0, 0)
 		}
 
 		// The signature could also look like: `fn on_finalize()`
@@ -178,28 +372,33 @@ decl_module! {
 			let (transactions, receipts): (Vec<_>, Vec<_>) =
 				transactions_and_receipts.into_iter().unzip();
 			let ommers = Vec::<ethereum::Header>::new();
+			let mut logs_bloom = Bloom::default();
+			for receipt in &receipts {
+				logs_bloom.accrue_bloom(&receipt.logs_bloom);
+			}
 
+			let number = U256::from(
+				UniqueSaturatedInto::<u128>::unique_saturated_into(
+					frame_system::Module::<T>::block_number()
+				)
+			);
 			let header = ethereum::Header {
-				parent_hash: frame_system::Module::<T>::parent_hash(),
+				parent_hash: pallet_evm::Module::<T>::block_hash(number.saturating_sub(U256::one())),
 				ommers_hash: H256::from_slice(
 					Keccak256::digest(&rlp::encode_list(&ommers)[..]).as_slice(),
 				), // TODO: check ommers hash.
 				beneficiary: <Module<T>>::find_author(),
-				state_root: H256::default(), // TODO: figure out if there's better way to get a sort-of-valid state root.
-				transactions_root: H256::from_slice(
-					Keccak256::digest(&rlp::encode_list(&transactions)[..]).as_slice(),
-				), // TODO: check transactions hash.
-				receipts_root: H256::from_slice(
-					Keccak256::digest(&rlp::encode_list(&receipts)[..]).as_slice(),
-				), // TODO: check receipts hash.
-				logs_bloom: Bloom::default(), // TODO: gather the logs bloom from receipts.
-				difficulty: U256::zero(),
-				number: U256::from(
-					UniqueSaturatedInto::<u128>::unique_saturated_into(
-						frame_system::Module::<T>::block_number()
-					)
+				state_root: pallet_evm::Module::<T>::commit_state(),
+				transactions_root: ethereum_trie::ordered_trie_root(
+					transactions.iter().map(|transaction| rlp::encode(transaction))
+				),
+				receipts_root: ethereum_trie::ordered_trie_root(
+					receipts.iter().map(|receipt| rlp::encode(receipt))
 				),
-				gas_limit: U256::zero(), // TODO: set this using Ethereum's gas limit change algorithm.
+				logs_bloom,
+				difficulty: U256::zero(),
+				number,
+				gas_limit: Self::block_gas_limit(),
 				gas_used: U256::zero(), // TODO: get this from receipts.
 				timestamp: UniqueSaturatedInto::<u64>::unique_saturated_into(
 					pallet_timestamp::Module::<T>::get()
@@ -209,6 +408,7 @@ decl_module! {
 				nonce: H64::default(),
 			};
 			let hash = H256::from_slice(Keccak256::digest(&rlp::encode(&header)).as_slice());
+			pallet_evm::Module::<T>::note_block_hash(n, hash);
 
 			let block = ethereum::Block {
 				header,
@@ -243,13 +443,45 @@ decl_module! {
 	}
 }
 
-impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
-	type Call = Call<T>;
+impl<T: Trait> Call<T> {
+	/// Whether the call is an Ethereum transaction, which carries its own signature.
+	pub fn is_self_contained(&self) -> bool {
+		match self {
+			Call::transact(..) => true,
+			_ => false,
+		}
+	}
 
-	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-		ValidTransaction::with_tag_prefix("Ethereum")
-			.and_provides(call)
-			.build()
+	/// Recover the sender of an Ethereum transaction from its signature.
+	pub fn check_self_contained(&self) -> Option<Result<H160, TransactionValidityError>> {
+		match self {
+			Call::transact(transaction) => Some(
+				Module::<T>::recover_signer(transaction).ok_or(InvalidTransaction::BadProof.into())
+			),
+			_ => None,
+		}
+	}
+
+	/// Validate an Ethereum transaction from its sender for the transaction pool, where it
+	/// may wait for the transactions of the sender with lower nonces.
+	pub fn validate_self_contained(&self, source: &H160) -> Option<TransactionValidity> {
+		match self {
+			Call::transact(transaction) =>
+				Some(Module::<T>::validate_transaction(*source, transaction, true)),
+			_ => None,
+		}
+	}
+
+	/// Validate an Ethereum transaction from its sender right before it is dispatched.
+	pub fn pre_dispatch_self_contained(
+		&self,
+		source: &H160,
+	) -> Option<Result<(), TransactionValidityError>> {
+		match self {
+			Call::transact(transaction) =>
+				Some(Module::<T>::validate_transaction(*source, transaction, false).map(|_| ())),
+			_ => None,
+		}
 	}
 }
 
@@ -260,13 +492,7 @@ impl<T: Trait> frame_support::unsigned::ValidateUnsigned for Module<T> {
 // - Private functions. These are your usual private utilities unavailable to other pallets.
 impl<T: Trait> Module<T> {
 	pub fn find_author() -> H160 {
-		let digest = <frame_system::Module<T>>::digest();
-		let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
-		if let Some(authority_id) = T::FindAuthor::find_author(pre_runtime_digests) {
-			authority_id
-		} else {
-			H160::default()
-		}
+		pallet_evm::Module::<T>::find_author()
 	}
 
 	pub fn transaction_status(hash: H256) -> Option<TransactionStatus> {
@@ -355,16 +581,112 @@ impl<T: Trait> Module<T> {
 		}).collect()
 	}
 
-	/// Execute an Ethereum transaction, ignoring transaction signatures.
-	pub fn execute(source: H160, transaction: ethereum::Transaction) {
+	/// Weight of an Ethereum transaction with a gas limit: the recovery of its sender, its
+	/// execution, and its share of the Ethereum block built in `on_finalize`, as if it emitted
+	/// as many logs as its gas allows and changed as many accounts and slots as it can.
+	pub fn transaction_weight(gas_limit: u32) -> Weight {
+		let block_share = <T as Trait>::WeightInfo::on_finalize(1, gas_limit / LOG_GAS)
+			.saturating_sub(<T as Trait>::WeightInfo::on_finalize(0, 0));
+
+		<T as Trait>::WeightInfo::recover_signer()
+			.saturating_add(<T as Trait>::WeightInfo::transact(gas_limit))
+			.saturating_add(block_share)
+			.saturating_add(pallet_evm::Module::<T>::execution_commit_weight(gas_limit))
+	}
+
+	/// Recover the sender of an Ethereum transaction signed for the chain id of the runtime.
+	pub fn recover_signer(transaction: &ethereum::Transaction) -> Option<H160> {
+		let mut sig = [0u8; 65];
+		let mut msg = [0u8; 32];
+		sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
+		sig[32..64].copy_from_slice(&transaction.signature.s()[..]);
+		sig[64] = transaction.signature.standard_v();
+		msg.copy_from_slice(&transaction.message_hash(Some(T::ChainId::get()))[..]);
+
+		let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg).ok()?;
+		Some(H160::from(H256::from_slice(Keccak256::digest(&pubkey).as_slice())))
+	}
+
+	/// Check an Ethereum transaction from its sender against the chain id, the gas limits and
+	/// the account of the sender. Transactions with a nonce ahead of the account are only valid
+	/// in the pool, where they require the transaction with the previous nonce.
+	fn validate_transaction(
+		source: H160,
+		transaction: &ethereum::Transaction,
+		in_pool: bool,
+	) -> TransactionValidity {
+		if transaction.signature.chain_id().unwrap_or_default() != T::ChainId::get() {
+			return Err(InvalidTransaction::BadProof.into())
+		}
+		if transaction.gas_limit > Self::block_gas_limit() {
+			return Err(InvalidTransaction::ExhaustsResources.into())
+		}
+		if transaction.gas_price < T::FeeCalculator::min_gas_price() {
+			return Err(InvalidTransaction::Payment.into())
+		}
+
+		let account = pallet_evm::Module::<T>::accounts(source);
+		if transaction.nonce < account.nonce {
+			return Err(InvalidTransaction::Stale.into())
+		}
+		if transaction.nonce > account.nonce && !in_pool {
+			return Err(InvalidTransaction::Future.into())
+		}
+
+		let target = match transaction.action {
+			ethereum::TransactionAction::Call(target) => Some(target),
+			ethereum::TransactionAction::Create => None,
+		};
+		if pallet_evm::Module::<T>::check_access(&source, target.as_ref()).is_err() {
+			return Err(InvalidTransaction::Call.into())
+		}
+
+		let fee_payer = pallet_evm::Module::<T>::fee_payer(
+			source,
+			target,
+			&transaction.input,
+			saturated_gas_limit(transaction.gas_limit),
+			transaction.gas_price,
+		);
+		let fee = transaction.gas_price.checked_mul(transaction.gas_limit)
+			.ok_or(InvalidTransaction::Payment)?;
+		if fee_payer == source {
+			let total_payment = fee.checked_add(transaction.value)
+				.ok_or(InvalidTransaction::Payment)?;
+			if account.balance < total_payment {
+				return Err(InvalidTransaction::Payment.into())
+			}
+		} else if account.balance < transaction.value ||
+			pallet_evm::Module::<T>::accounts(fee_payer).balance < fee
+		{
+			return Err(InvalidTransaction::Payment.into())
+		}
+
+		let mut builder = ValidTransaction::with_tag_prefix("Ethereum")
+			.priority(transaction.gas_price.min(U256::from(u64::max_value())).low_u64())
+			.and_provides((source, transaction.nonce));
+		if transaction.nonce > account.nonce {
+			builder = builder.and_requires((source, transaction.nonce - U256::one()));
+		}
+		builder.build()
+	}
+
+	/// Execute an Ethereum transaction, ignoring transaction signatures. Nothing is changed
+	/// if the transaction cannot pay for its execution.
+	pub fn execute(source: H160, transaction: ethereum::Transaction) -> DispatchResult {
+		ensure!(
+			transaction.gas_limit <= U256::from(u32::max_value()),
+			Error::<T>::GasLimitTooHigh
+		);
+
 		let transaction_hash = H256::from_slice(
 			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
 		);
 		let transaction_index = PendingTransactionsAndReceipts::get().len() as u32;
 
-		let status = match transaction.action {
+		let (to, contract_address, logs, exit_reason, fee_payer) = match transaction.action {
 			ethereum::TransactionAction::Call(target) => {
-				pallet_evm::Module::<T>::execute_call(
+				let info = pallet_evm::Module::<T>::execute_call(
 					source,
 					target,
 					transaction.input.clone(),
@@ -373,20 +695,12 @@ impl<T: Trait> Module<T> {
 					transaction.gas_price,
 					Some(transaction.nonce),
 					true,
-				).unwrap(); // TODO: handle error
-
-				TransactionStatus {
-					transaction_hash,
-					transaction_index,
-					from: source,
-					to: Some(target),
-					contract_address: None,
-					logs: Vec::new(), // TODO: feed in logs.
-					logs_bloom: Bloom::default(), // TODO: feed in bloom.
-				}
+				)?;
+
+				(Some(target), None, info.logs, info.exit_reason, info.fee_payer)
 			},
 			ethereum::TransactionAction::Create => {
-				let contract_address = pallet_evm::Module::<T>::execute_create(
+				let info = pallet_evm::Module::<T>::execute_create(
 					source,
 					transaction.input.clone(),
 					transaction.value,
@@ -394,29 +708,53 @@ impl<T: Trait> Module<T> {
 					transaction.gas_price,
 					Some(transaction.nonce),
 					true,
-				).unwrap().1; // TODO: handle error
-
-				TransactionStatus {
-					transaction_hash,
-					transaction_index,
-					from: source,
-					to: None,
-					contract_address: Some(contract_address),
-					logs: Vec::new(), // TODO: feed in logs.
-					logs_bloom: Bloom::default(), // TODO: feed in bloom.
-				}
+				)?;
+
+				(None, Some(info.value), info.logs, info.exit_reason, info.fee_payer)
 			},
 		};
 
+		let logs = logs.into_iter().map(|log| Log {
+			address: log.address,
+			topics: log.topics,
+			data: log.data,
+		}).collect::<Vec<_>>();
+		let logs_bloom = ethereum::logs_bloom(&logs);
+
+		let status = TransactionStatus {
+			transaction_hash,
+			transaction_index,
+			from: source,
+			to,
+			contract_address,
+			logs: logs.clone(),
+			logs_bloom,
+			sponsor: if fee_payer == source { None } else { Some(fee_payer) },
+		};
+
 		TransactionStatuses::insert(transaction_hash, status);
 
 		let receipt = ethereum::Receipt {
 			state_root: H256::default(), // TODO: should be okay / error status.
 			used_gas: U256::default(), // TODO: set this.
-			logs_bloom: Bloom::default(), // TODO: set this.
-			logs: Vec::new(), // TODO: set this.
+			logs_bloom,
+			logs,
 		};
 
 		PendingTransactionsAndReceipts::append((transaction, receipt));
+
+		Self::deposit_event(Event::Executed(
+			source,
+			to.or(contract_address).unwrap_or_default(),
+			transaction_hash,
+			exit_reason.into(),
+		));
+
+		Ok(())
 	}
 }
+
+/// Gas limit of a transaction as a `u32`, saturating above `u32::max_value()`.
+pub fn saturated_gas_limit(gas_limit: U256) -> u32 {
+	gas_limit.min(U256::from(u32::max_value())).low_u32()
+}
@@ -20,9 +20,10 @@ use super::*;
 use crate::{Module, Trait};
 use ethereum::{TransactionAction, TransactionSignature};
 use frame_support::{
-	impl_outer_origin, parameter_types, weights::Weight, ConsensusEngineId
+	impl_outer_origin, impl_outer_event, parameter_types, weights::Weight, traits::FindAuthor,
+	ConsensusEngineId,
 };
-use pallet_evm::{FeeCalculator, HashTruncateConvertAccountId};
+use pallet_evm::{FeeCalculator, EvmAccessControl, HashTruncateConvertAccountId};
 use rlp::*;
 use sp_core::{H160, H256, U256};
 use sp_runtime::{
@@ -31,8 +32,19 @@ use sp_runtime::{
 	ModuleId, Perbill,
 };
 
+use crate as pallet_ethereum;
+
 impl_outer_origin! {
-	pub enum Origin for Test where system = frame_system {}
+	pub enum Origin for Test where system = frame_system {
+		pallet_ethereum,
+	}
+}
+
+impl_outer_event! {
+	pub enum TestEvent for Test {
+		frame_system<T>,
+		pallet_ethereum,
+	}
 }
 
 // For testing the pallet, we construct most of a mock runtime. This means
@@ -58,7 +70,7 @@ impl frame_system::Trait for Test {
 	type AccountId = H160;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = TestEvent;
 	type BlockHashCount = BlockHashCount;
 	type MaximumBlockWeight = MaximumBlockWeight;
 	type DbWeight = ();
@@ -114,6 +126,26 @@ impl FindAuthor<H160> for EthereumFindAuthor {
 	}
 }
 
+/// Contract nobody may call.
+pub fn restricted() -> H160 {
+	H160::repeat_byte(0xee)
+}
+
+pub struct RestrictedCalls;
+impl EvmAccessControl for RestrictedCalls {
+	fn can_create(_source: &H160) -> bool { true }
+
+	fn can_call(_source: &H160, target: &H160) -> bool { *target != restricted() }
+}
+
+/// Block gas limit set in the genesis of the module, as runtimes configure it.
+pub struct BlockGasLimit;
+impl Get<U256> for BlockGasLimit {
+	fn get() -> U256 {
+		Ethereum::block_gas_limit()
+	}
+}
+
 parameter_types! {
 	pub const TransactionByteFee: u64 = 1;
 	pub const ChainId: u64 = 42;
@@ -128,11 +160,19 @@ impl pallet_evm::Trait for Test {
 	type Event = ();
 	type Precompiles = ();
 	type ChainId = ChainId;
+	type BlockGasLimit = BlockGasLimit;
+	type FindAuthor = EthereumFindAuthor;
+	type OnChargeTransaction = ();
+	type FeePayer = ();
+	type AccessControl = RestrictedCalls;
+	type EmergencyOrigin = frame_system::EnsureRoot<H160>;
+	type WeightInfo = ();
 }
 
 impl Trait for Test {
-	type Event = ();
-	type FindAuthor = EthereumFindAuthor;
+	type Event = TestEvent;
+	type RuntimeOrigin = Origin;
+	type WeightInfo = ();
 }
 
 pub type System = frame_system::Module<Test>;
@@ -18,11 +18,11 @@
 //! Consensus extension module tests for BABE consensus.
 
 use super::*;
-use mock::*;
+use mock::{*, Origin};
 use rustc_hex::FromHex;
 use std::str::FromStr;
 use ethereum::TransactionSignature;
-use frame_support::assert_noop;
+use frame_support::{assert_noop, assert_ok, traits::OnFinalize, weights::GetDispatchInfo};
 
 // This ERC-20 contract mints the maximum amount of tokens to the contract creator.
 // pragma solidity ^0.5.0;
@@ -49,10 +49,10 @@ fn transaction_should_increment_nonce() {
 	let alice = &pairs[0];
 
 	ext.execute_with(|| {
-		Ethereum::execute(
+		assert_ok!(Ethereum::execute(
 			alice.address,
 			default_erc20_creation_transaction(alice),
-		);
+		));
 		assert_eq!(Evm::accounts(alice.address).nonce, U256::from(1));
 	});
 }
@@ -65,10 +65,10 @@ fn transaction_should_be_added_to_pending() {
 
 	ext.execute_with(|| {
 		let transaction = default_erc20_creation_transaction(alice);
-		Ethereum::execute(
+		assert_ok!(Ethereum::execute(
 			alice.address,
 			transaction.clone(),
-		);
+		));
 		assert_eq!(PendingTransactionsAndReceipts::get().len(), 1);
 		assert_eq!(PendingTransactionsAndReceipts::get()[0].0.input, transaction.input);
 	});
@@ -76,7 +76,6 @@ fn transaction_should_be_added_to_pending() {
 
 
 #[test]
-#[should_panic(expected = "called `Result::unwrap()` on an `Err` value: BalanceLow")]
 fn transaction_without_enough_gas_should_not_work() {
 	let (pairs, mut ext) = new_test_ext(1);
 	let alice = &pairs[0];
@@ -85,9 +84,33 @@ fn transaction_without_enough_gas_should_not_work() {
 		let mut transaction = default_erc20_creation_transaction(alice);
 		transaction.gas_price = U256::from(1);
 
-		Ethereum::execute(
-			alice.address,
-			transaction,
+		assert_noop!(
+			Ethereum::execute(
+				alice.address,
+				transaction,
+			),
+			pallet_evm::Error::<Test>::BalanceLow,
+		);
+		assert_eq!(PendingTransactionsAndReceipts::get().len(), 0);
+	});
+}
+
+#[test]
+fn transaction_with_gas_limit_above_u32_should_not_work() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		let mut transaction = default_erc20_creation_transaction(alice);
+		transaction.gas_limit = U256::from(u32::max_value()) + U256::one();
+
+		assert_eq!(
+			Call::<Test>::transact(transaction.clone()).get_dispatch_info().weight,
+			Ethereum::transaction_weight(u32::max_value()),
+		);
+		assert_noop!(
+			Ethereum::execute(alice.address, transaction),
+			Error::<Test>::GasLimitTooHigh,
 		);
 	});
 }
@@ -100,12 +123,12 @@ fn contract_constructor_should_get_executed() {
 	let alice_storage_address = storage_address(alice.address, H256::zero());
 
 	ext.execute_with(|| {
-		Ethereum::execute(
+		assert_ok!(Ethereum::execute(
 			alice.address,
 			default_erc20_creation_transaction(alice),
-		);
+		));
 		assert_eq!(Evm::account_storages(
-			erc20_address, alice_storage_address
+			&erc20_address, &alice_storage_address
 		), H256::from_str("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff").unwrap())
 
 	});
@@ -120,14 +143,21 @@ fn source_should_be_derived_from_signature() {
 	let alice_storage_address = storage_address(alice.address, H256::zero());
 
 	ext.execute_with(|| {
+		let transaction = default_erc20_creation_transaction(alice);
+		let source = Call::<Test>::transact(transaction.clone())
+			.check_self_contained()
+			.unwrap()
+			.expect("Failed to recover the sender");
+		assert_eq!(source, alice.address);
+
 		Ethereum::transact(
-			Origin::none(),
-			default_erc20_creation_transaction(alice),
+			RawOrigin::EthereumTransaction(source).into(),
+			transaction,
 		).expect("Failed to execute transaction");
 
 		// We verify the transaction happened with alice account.
 		assert_eq!(Evm::account_storages(
-			erc20_address, alice_storage_address
+			&erc20_address, &alice_storage_address
 		), H256::from_str("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff").unwrap())
 
 	});
@@ -140,11 +170,62 @@ fn invalid_signature_should_be_ignored() {
 
 	let mut transaction = default_erc20_creation_transaction(alice);
 	transaction.signature = TransactionSignature::new(0x78, H256::from_slice(&[55u8;32]), H256::from_slice(&[55u8;32])).unwrap();
+	ext.execute_with(|| {
+		assert_eq!(
+			Call::<Test>::transact(transaction).check_self_contained(),
+			Some(Err(InvalidTransaction::BadProof.into())),
+		);
+	});
+}
+
+#[test]
+fn transactions_should_require_an_ethereum_origin() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
 	ext.execute_with(|| {
 		assert_noop!(Ethereum::transact(
 			Origin::none(),
-			transaction,
-		), "Recover public key failed");
+			default_erc20_creation_transaction(alice),
+		), "bad origin: expected to be an Ethereum transaction");
+	});
+}
+
+#[test]
+fn self_contained_transactions_should_be_validated_against_sender_nonce() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	let transaction = |nonce: u64, gas_price: u64| Call::<Test>::transact(UnsignedTransaction {
+		nonce: U256::from(nonce),
+		gas_price: U256::from(gas_price),
+		gas_limit: U256::zero(),
+		action: ethereum::TransactionAction::Call(H160::default()),
+		value: U256::zero(),
+		input: Vec::new(),
+	}.sign(&alice.private_key));
+
+	ext.execute_with(|| {
+		assert_ok!(Ethereum::execute(alice.address, default_erc20_creation_transaction(alice)));
+
+		assert_eq!(
+			transaction(0, 1).validate_self_contained(&alice.address),
+			Some(Err(InvalidTransaction::Stale.into())),
+		);
+		assert_eq!(
+			transaction(1, 0).validate_self_contained(&alice.address),
+			Some(Err(InvalidTransaction::Payment.into())),
+		);
+		assert_eq!(transaction(1, 1).pre_dispatch_self_contained(&alice.address), Some(Ok(())));
+
+		// Transactions ahead of the sender nonce wait in the pool.
+		let future = transaction(2, 1);
+		let valid = future.validate_self_contained(&alice.address).unwrap().unwrap();
+		assert_eq!(valid.requires, vec![("Ethereum", alice.address, U256::from(1)).encode()]);
+		assert_eq!(
+			future.pre_dispatch_self_contained(&alice.address),
+			Some(Err(InvalidTransaction::Future.into())),
+		);
 	});
 }
 
@@ -157,10 +238,120 @@ fn contract_should_be_created_at_given_address() {
 	let erc20_address = contract_address(alice.address, 0);
 
 	ext.execute_with(|| {
-		Ethereum::execute(
+		assert_ok!(Ethereum::execute(
 			alice.address,
 			default_erc20_creation_transaction(alice),
-		);
+		));
 		assert_ne!(Evm::account_codes(erc20_address).len(), 0);
 	});
 }
+
+#[test]
+fn blocks_should_be_chained_by_ethereum_hashes() {
+	let (_, mut ext) = new_test_ext(1);
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Ethereum::on_finalize(1);
+		System::set_block_number(2);
+		Ethereum::on_finalize(2);
+
+		let first = BlockNumbers::<Test>::get(1);
+		let second = BlockNumbers::<Test>::get(2);
+		let (block, _) = BlocksAndReceipts::get(second).unwrap();
+		assert_eq!(block.header.parent_hash, first);
+
+		System::set_block_number(3);
+		assert_eq!(Evm::block_hash(U256::from(1)), first);
+		assert_eq!(Evm::block_hash(U256::from(2)), second);
+		assert_eq!(Evm::block_hash(U256::from(3)), H256::default());
+	});
+}
+
+#[test]
+fn exit_reasons_should_be_encodable() {
+	let reasons = vec![
+		pallet_evm::ExitReason::Succeed(pallet_evm::ExitSucceed::Returned),
+		pallet_evm::ExitReason::Revert(pallet_evm::ExitRevert::Reverted),
+		pallet_evm::ExitReason::Error(pallet_evm::ExitError::Other("out of luck")),
+		pallet_evm::ExitReason::Fatal(
+			pallet_evm::ExitFatal::CallErrorAsFatal(pallet_evm::ExitError::OutOfGas)
+		),
+	];
+
+	for reason in reasons {
+		let reason = ExitReason::from(reason);
+		assert_eq!(ExitReason::decode(&mut &reason.encode()[..]).ok(), Some(reason));
+	}
+	assert_eq!(
+		ExitReason::from(pallet_evm::ExitReason::Error(pallet_evm::ExitError::Other("out of luck"))),
+		ExitReason::Error(ExitError::Other(b"out of luck".to_vec())),
+	);
+}
+
+#[test]
+fn gas_limits_above_u32_should_saturate() {
+	assert_eq!(saturated_gas_limit(U256::from(21000)), 21000);
+	assert_eq!(saturated_gas_limit(U256::from(u32::max_value()) + 1), u32::max_value());
+	assert_eq!(saturated_gas_limit(U256::MAX), u32::max_value());
+}
+
+#[test]
+fn chain_id_and_gas_limit_should_default_without_genesis() {
+	let (_, mut ext) = new_test_ext(0);
+	ext.execute_with(|| {
+		assert_eq!(Ethereum::chain_id(), DEFAULT_CHAIN_ID);
+		assert_eq!(Ethereum::block_gas_limit(), U256::from(DEFAULT_BLOCK_GAS_LIMIT));
+	});
+}
+
+#[test]
+#[should_panic(expected = "Ethereum chain id must not be zero")]
+fn genesis_with_zero_chain_id_should_be_rejected() {
+	let _ = GenesisConfig {
+		chain_id: 0,
+		block_gas_limit: U256::from(DEFAULT_BLOCK_GAS_LIMIT),
+	}.build_storage::<Test>();
+}
+
+#[test]
+#[should_panic(expected = "Ethereum block gas limit must not be zero")]
+fn genesis_with_zero_block_gas_limit_should_be_rejected() {
+	let _ = GenesisConfig {
+		chain_id: DEFAULT_CHAIN_ID,
+		block_gas_limit: U256::zero(),
+	}.build_storage::<Test>();
+}
+
+#[test]
+fn calls_rejected_by_access_control_are_executed_as_failed() {
+	let (pairs, mut ext) = new_test_ext(1);
+	let alice = &pairs[0];
+
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		let transaction = UnsignedTransaction {
+			nonce: U256::zero(),
+			gas_price: U256::zero(),
+			gas_limit: U256::from(0x100000),
+			action: ethereum::TransactionAction::Call(restricted()),
+			value: U256::zero(),
+			input: Vec::new(),
+		}.sign(&alice.private_key);
+		let transaction_hash = H256::from_slice(
+			Keccak256::digest(&rlp::encode(&transaction)).as_slice()
+		);
+
+		assert_ok!(Ethereum::execute(alice.address, transaction));
+		assert_eq!(Evm::accounts(alice.address).nonce, U256::one());
+		assert_eq!(
+			System::events().pop().map(|record| record.event),
+			Some(TestEvent::pallet_ethereum(Event::Executed(
+				alice.address,
+				restricted(),
+				transaction_hash,
+				ExitReason::Error(ExitError::CallNotAllowed),
+			))),
+		);
+	});
+}
@@ -0,0 +1,195 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # EVM access control pallet
+//!
+//! The EVM access control pallet runs the EVM in a permissioned mode, where an admin origin,
+//! such as root or a collective, controls who may send EVM transactions:
+//!
+//! - While the EVM is paused, no transaction is allowed.
+//! - If deployers are restricted, only allowed deployers may create contracts.
+//! - If the callers of a contract are restricted, only its allowed callers may call it.
+//!
+//! The pallet is plugged into `pallet_evm` as its `AccessControl`, which checks transactions
+//! in the transaction pool, and every call and creation when they are executed, including
+//! those made by contracts.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{decl_module, decl_storage, decl_event, traits::EnsureOrigin};
+use sp_std::prelude::*;
+use sp_core::H160;
+use pallet_evm::EvmAccessControl;
+
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+#[cfg(all(feature = "std", test))]
+mod mock;
+
+pub trait Trait: pallet_evm::Trait {
+	/// The overarching event type.
+	type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+	/// Origin managing the pause and the allowlists.
+	type AdminOrigin: EnsureOrigin<Self::Origin>;
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as EvmAccessControl {
+		/// Whether all EVM transactions are rejected.
+		Paused get(fn paused) config(): bool;
+		/// Whether only allowed deployers may create contracts.
+		DeployersRestricted get(fn deployers_restricted) config(): bool;
+		/// Deployers allowed to create contracts when deployers are restricted.
+		Deployers get(fn is_deployer): map hasher(blake2_128_concat) H160 => bool;
+		/// Contracts which only allowed callers may call.
+		CallersRestricted get(fn callers_restricted): map hasher(blake2_128_concat) H160 => bool;
+		/// Callers allowed to call each contract whose callers are restricted.
+		Callers get(fn is_caller):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H160 => bool;
+	}
+	add_extra_genesis {
+		config(deployers): Vec<H160>;
+		build(|config: &GenesisConfig| {
+			for deployer in &config.deployers {
+				Deployers::insert(deployer, true);
+			}
+		});
+	}
+}
+
+decl_event! {
+	/// EVM access control events
+	pub enum Event {
+		/// All EVM transactions are rejected.
+		Paused,
+		/// EVM transactions are no longer all rejected.
+		Unpaused,
+		/// Deployers were restricted to the allowed ones, or no longer restricted.
+		DeployersRestricted(bool),
+		/// Deployers were allowed.
+		DeployersAllowed(Vec<H160>),
+		/// Deployers were disallowed.
+		DeployersDisallowed(Vec<H160>),
+		/// The callers of a contract were restricted to the allowed ones, or no longer restricted.
+		CallersRestricted(H160, bool),
+		/// Callers of a contract were allowed.
+		CallersAllowed(H160, Vec<H160>),
+		/// Callers of a contract were disallowed.
+		CallersDisallowed(H160, Vec<H160>),
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		fn deposit_event() = default;
+
+		/// Reject all EVM transactions.
+		#[weight = 0]
+		fn pause(origin) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Paused::put(true);
+			Self::deposit_event(Event::Paused);
+		}
+
+		/// Stop rejecting all EVM transactions.
+		#[weight = 0]
+		fn unpause(origin) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			Paused::put(false);
+			Self::deposit_event(Event::Unpaused);
+		}
+
+		/// Restrict deployers to the allowed ones, or stop restricting them.
+		#[weight = 0]
+		fn set_deployers_restricted(origin, restricted: bool) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			DeployersRestricted::put(restricted);
+			Self::deposit_event(Event::DeployersRestricted(restricted));
+		}
+
+		/// Allow deployers to create contracts.
+		#[weight = 0]
+		fn allow_deployers(origin, deployers: Vec<H160>) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			for deployer in &deployers {
+				Deployers::insert(deployer, true);
+			}
+			Self::deposit_event(Event::DeployersAllowed(deployers));
+		}
+
+		/// Disallow deployers to create contracts.
+		#[weight = 0]
+		fn disallow_deployers(origin, deployers: Vec<H160>) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			for deployer in &deployers {
+				Deployers::remove(deployer);
+			}
+			Self::deposit_event(Event::DeployersDisallowed(deployers));
+		}
+
+		/// Restrict the callers of a contract to the allowed ones, or stop restricting them.
+		#[weight = 0]
+		fn set_callers_restricted(origin, contract: H160, restricted: bool) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			if restricted {
+				CallersRestricted::insert(contract, true);
+			} else {
+				CallersRestricted::remove(contract);
+			}
+			Self::deposit_event(Event::CallersRestricted(contract, restricted));
+		}
+
+		/// Allow callers to call a contract.
+		#[weight = 0]
+		fn allow_callers(origin, contract: H160, callers: Vec<H160>) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			for caller in &callers {
+				Callers::insert(contract, caller, true);
+			}
+			Self::deposit_event(Event::CallersAllowed(contract, callers));
+		}
+
+		/// Disallow callers to call a contract.
+		#[weight = 0]
+		fn disallow_callers(origin, contract: H160, callers: Vec<H160>) {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			for caller in &callers {
+				Callers::remove(contract, caller);
+			}
+			Self::deposit_event(Event::CallersDisallowed(contract, callers));
+		}
+	}
+}
+
+impl<T: Trait> EvmAccessControl for Module<T> {
+	fn can_create(source: &H160) -> bool {
+		!Self::paused() && (!Self::deployers_restricted() || Self::is_deployer(source))
+	}
+
+	fn can_call(source: &H160, target: &H160) -> bool {
+		!Self::paused() && (!Self::callers_restricted(target) || Self::is_caller(target, source))
+	}
+}
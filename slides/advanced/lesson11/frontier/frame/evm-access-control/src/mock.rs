@@ -0,0 +1,189 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+use super::*;
+use crate::{Module, Trait};
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use frame_system::EnsureRoot;
+use pallet_evm::{ExitReason, HashTruncateConvertAccountId};
+use sp_core::{H256, U256};
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	ModuleId, Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = H256;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 6000 / 2;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+	pub BlockGasLimit: U256 = U256::from(15_000_000);
+	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
+}
+
+impl pallet_evm::Trait for Test {
+	type ModuleId = EVMModuleId;
+	type FeeCalculator = ();
+	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = ();
+	type Precompiles = ();
+	type ChainId = ChainId;
+	type BlockGasLimit = BlockGasLimit;
+	type FindAuthor = ();
+	type OnChargeTransaction = ();
+	type FeePayer = ();
+	type AccessControl = AccessControl;
+	type EmergencyOrigin = EnsureRoot<H256>;
+	type WeightInfo = ();
+}
+
+impl Trait for Test {
+	type Event = ();
+	type AdminOrigin = EnsureRoot<H256>;
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Evm = pallet_evm::Module<Test>;
+pub type AccessControl = Module<Test>;
+
+/// Senders of EVM transactions.
+pub fn alice() -> H160 { H160::repeat_byte(0xaa) }
+pub fn bob() -> H160 { H160::repeat_byte(0xbb) }
+
+/// A contract, which does nothing as it has no code.
+pub fn contract() -> H160 { H160::repeat_byte(0xcc) }
+
+/// Call `contract` from `source`, returning why the call failed, if it did.
+pub fn call(source: H160) -> Result<(), ExitReason> {
+	let info = Evm::execute_call(source, contract(), Vec::new(), U256::zero(), 100_000, U256::zero(), None, true)
+		.unwrap();
+	if info.exit_reason.is_succeed() { Ok(()) } else { Err(info.exit_reason) }
+}
+
+/// Create a contract from `source`, returning why the creation failed, if it did.
+pub fn create(source: H160) -> Result<(), ExitReason> {
+	let info = Evm::execute_create(source, Vec::new(), U256::zero(), 100_000, U256::zero(), None, true)
+		.unwrap();
+	if info.exit_reason.is_succeed() { Ok(()) } else { Err(info.exit_reason) }
+}
+
+/// Code creating an empty contract and returning its address, zero if the creation failed.
+pub fn factory_code() -> Vec<u8> {
+	vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0xf0, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]
+}
+
+/// Code calling `contract` and returning whether the call succeeded.
+pub fn proxy_code() -> Vec<u8> {
+	let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x73];
+	code.extend_from_slice(contract().as_bytes());
+	code.extend_from_slice(&[0x5a, 0xf1, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+	code
+}
+
+/// Deploy `code` from `source`, returning the address of the contract.
+pub fn deploy(source: H160, code: Vec<u8>) -> H160 {
+	let len = code.len() as u8;
+	let mut init = vec![0x60, len, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xf3];
+	init.extend(code);
+	Evm::execute_create(source, init, U256::zero(), 1_000_000, U256::zero(), None, true)
+		.unwrap()
+		.value
+}
+
+/// Call `target` from `source`, returning the word it returns.
+pub fn call_word(source: H160, target: H160) -> H256 {
+	let info = Evm::execute_call(source, target, Vec::new(), U256::zero(), 1_000_000, U256::zero(), None, true)
+		.unwrap();
+	H256::from_slice(&info.value)
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	GenesisConfig {
+		paused: false,
+		deployers_restricted: true,
+		deployers: vec![alice()],
+	}.assimilate_storage(&mut t).unwrap();
+
+	t.into()
+}
@@ -0,0 +1,112 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! EVM access control pallet tests
+
+use super::*;
+use mock::*;
+use frame_support::{assert_noop, assert_ok};
+use pallet_evm::ExitError;
+use sp_core::H256;
+use sp_runtime::DispatchError;
+
+#[test]
+fn pausing_rejects_all_transactions() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(call(bob()));
+		assert_ok!(create(alice()));
+
+		assert_ok!(AccessControl::pause(Origin::root()));
+		assert_eq!(call(bob()), Err(ExitError::CallNotAllowed.into()));
+		assert_eq!(create(alice()), Err(ExitError::CreateNotAllowed.into()));
+
+		assert_ok!(AccessControl::unpause(Origin::root()));
+		assert_ok!(call(bob()));
+	});
+}
+
+#[test]
+fn only_allowed_deployers_create_contracts() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(create(bob()), Err(ExitError::CreateNotAllowed.into()));
+
+		assert_ok!(AccessControl::allow_deployers(Origin::root(), vec![bob()]));
+		assert_ok!(create(bob()));
+
+		assert_ok!(AccessControl::disallow_deployers(Origin::root(), vec![alice(), bob()]));
+		assert_eq!(create(alice()), Err(ExitError::CreateNotAllowed.into()));
+
+		assert_ok!(AccessControl::set_deployers_restricted(Origin::root(), false));
+		assert_ok!(create(bob()));
+	});
+}
+
+#[test]
+fn only_allowed_callers_call_restricted_contracts() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AccessControl::set_callers_restricted(Origin::root(), contract(), true));
+		assert_ok!(AccessControl::allow_callers(Origin::root(), contract(), vec![alice()]));
+		assert_ok!(call(alice()));
+		assert_eq!(call(bob()), Err(ExitError::CallNotAllowed.into()));
+
+		assert_ok!(AccessControl::disallow_callers(Origin::root(), contract(), vec![alice()]));
+		assert_eq!(call(alice()), Err(ExitError::CallNotAllowed.into()));
+
+		assert_ok!(AccessControl::set_callers_restricted(Origin::root(), contract(), false));
+		assert_ok!(call(bob()));
+	});
+}
+
+#[test]
+fn contracts_created_by_contracts_are_checked() {
+	new_test_ext().execute_with(|| {
+		let factory = deploy(alice(), factory_code());
+		assert_eq!(call_word(alice(), factory), H256::zero());
+
+		assert_ok!(AccessControl::allow_deployers(Origin::root(), vec![factory]));
+		assert_ne!(call_word(alice(), factory), H256::zero());
+	});
+}
+
+#[test]
+fn calls_made_by_contracts_are_checked() {
+	new_test_ext().execute_with(|| {
+		let proxy = deploy(alice(), proxy_code());
+		assert_eq!(call_word(alice(), proxy), H256::from_low_u64_be(1));
+
+		assert_ok!(AccessControl::set_callers_restricted(Origin::root(), contract(), true));
+		assert_ok!(AccessControl::allow_callers(Origin::root(), contract(), vec![alice()]));
+		assert_ok!(call(alice()));
+		assert_eq!(call_word(alice(), proxy), H256::zero());
+
+		assert_ok!(AccessControl::allow_callers(Origin::root(), contract(), vec![proxy]));
+		assert_eq!(call_word(alice(), proxy), H256::from_low_u64_be(1));
+	});
+}
+
+#[test]
+fn lists_are_managed_by_the_admin_origin() {
+	new_test_ext().execute_with(|| {
+		let origin = || Origin::signed(H256::repeat_byte(1));
+
+		assert_noop!(AccessControl::pause(origin()), DispatchError::BadOrigin);
+		assert_noop!(AccessControl::allow_deployers(origin(), vec![bob()]), DispatchError::BadOrigin);
+		assert_noop!(
+			AccessControl::set_callers_restricted(origin(), contract(), true),
+			DispatchError::BadOrigin,
+		);
+	});
+}
@@ -0,0 +1,215 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Paymaster pallet benchmarking.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_benchmarking::{benchmarks, account};
+use frame_support::traits::{Currency, OnInitialize, UnfilteredDispatchable};
+use sp_runtime::traits::Saturating;
+use pallet_evm::BalanceOf;
+
+const SEED: u32 = 0;
+/// Maximum number of callers allowed or disallowed at once.
+const MAX_CALLERS: u32 = 1_000;
+/// Maximum number of function selectors of the benchmarked rules.
+const MAX_SELECTORS: u32 = 1_000;
+/// EVM balance of the owner of the benchmarked sponsorship.
+const FUNDS: u32 = 1_000_000;
+
+fn caller(index: u32) -> H160 {
+	H160::from_low_u64_be(index as u64 + 1)
+}
+
+/// Owner of a sponsored contract, with `FUNDS` deposited to its EVM address, along with the
+/// contract.
+fn registered<T: Trait>() -> Result<(T::AccountId, H160), &'static str> {
+	let owner: T::AccountId = account("owner", 0, SEED);
+	let value: BalanceOf<T> = FUNDS.into();
+	T::Currency::make_free_balance_be(&owner, value.saturating_add(value));
+	pallet_evm::Call::<T>::deposit_balance(value)
+		.dispatch_bypass_filter(RawOrigin::Signed(owner.clone()).into())?;
+
+	let contract = H160::repeat_byte(0xcc);
+	Module::<T>::do_register(contract, owner.clone())?;
+	Ok((owner, contract))
+}
+
+benchmarks! {
+	_ { }
+
+	register {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let creator = T::ConvertAccountId::convert_account_id(&owner);
+		let contract = Module::<T>::create_address(creator, U256::zero());
+	}: _(RawOrigin::Signed(owner), contract, U256::zero())
+	verify {
+		assert!(Sponsorships::<T>::contains_key(&contract));
+	}
+
+	force_register {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let contract = H160::repeat_byte(0xcc);
+	}: _(RawOrigin::Root, contract, owner)
+	verify {
+		assert!(Sponsorships::<T>::contains_key(&contract));
+	}
+
+	// Worst case: the sponsorship has funds to return.
+	unregister {
+		let (owner, contract) = registered::<T>()?;
+		Call::<T>::fund(contract, U256::from(FUNDS))
+			.dispatch_bypass_filter(RawOrigin::Signed(owner.clone()).into())?;
+	}: _(RawOrigin::Signed(owner), contract)
+	verify {
+		assert!(RemovalQueue::contains_key(&contract));
+	}
+
+	set_rules {
+		let s in 0 .. MAX_SELECTORS;
+
+		let (owner, contract) = registered::<T>()?;
+		let rules = SponsorshipRules {
+			restrict_callers: true,
+			selectors: Some((0 .. s).map(|i| i.to_be_bytes()).collect()),
+			max_gas_price: Some(U256::one()),
+			quota: Some(U256::one()),
+		};
+	}: _(RawOrigin::Signed(owner), contract, rules.clone())
+	verify {
+		assert_eq!(Module::<T>::sponsorships(&contract).map(|sponsorship| sponsorship.rules), Some(rules));
+	}
+
+	allow_callers {
+		let c in 0 .. MAX_CALLERS;
+
+		let (owner, contract) = registered::<T>()?;
+		let callers = (0 .. c).map(caller).collect::<Vec<_>>();
+	}: _(RawOrigin::Signed(owner), contract, callers)
+	verify {
+		assert_eq!(AllowedCallers::iter_prefix(&contract).count(), c as usize);
+	}
+
+	disallow_callers {
+		let c in 0 .. MAX_CALLERS;
+
+		let (owner, contract) = registered::<T>()?;
+		let callers = (0 .. c).map(caller).collect::<Vec<_>>();
+		for caller in &callers {
+			AllowedCallers::insert(&contract, caller, true);
+		}
+	}: _(RawOrigin::Signed(owner), contract, callers)
+	verify {
+		assert_eq!(AllowedCallers::iter_prefix(&contract).count(), 0);
+	}
+
+	fund {
+		let (owner, contract) = registered::<T>()?;
+	}: _(RawOrigin::Signed(owner), contract, U256::from(FUNDS))
+	verify {
+		let sponsor = Module::<T>::sponsor_address(&contract);
+		assert_eq!(pallet_evm::Module::<T>::accounts(&sponsor).balance, U256::from(FUNDS));
+	}
+
+	defund {
+		let (owner, contract) = registered::<T>()?;
+		Call::<T>::fund(contract, U256::from(FUNDS))
+			.dispatch_bypass_filter(RawOrigin::Signed(owner.clone()).into())?;
+	}: _(RawOrigin::Signed(owner), contract, U256::from(FUNDS))
+	verify {
+		let sponsor = Module::<T>::sponsor_address(&contract);
+		assert_eq!(pallet_evm::Module::<T>::accounts(&sponsor).balance, U256::zero());
+	}
+
+	// Removed entries are half allowed callers, half spending records.
+	on_initialize {
+		let r in 0 .. REMOVAL_LIMIT;
+
+		let contract = H160::repeat_byte(0xcc);
+		for i in 0 .. r {
+			if i % 2 == 0 {
+				AllowedCallers::insert(&contract, &caller(i), true);
+			} else {
+				Spent::<T>::insert(&contract, &caller(i), (T::BlockNumber::zero(), U256::one()));
+			}
+		}
+		RemovalQueue::insert(&contract, ());
+	}: { Module::<T>::on_initialize(Zero::zero()) }
+	verify {
+		assert_eq!(AllowedCallers::iter_prefix(&contract).count(), 0);
+		assert_eq!(Spent::<T>::iter_prefix(&contract).count(), 0);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{new_test_ext, Test};
+	use frame_support::assert_ok;
+
+	#[test]
+	fn register() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_register::<Test>());
+		});
+	}
+
+	#[test]
+	fn unregister() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_unregister::<Test>());
+		});
+	}
+
+	#[test]
+	fn set_rules() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_set_rules::<Test>());
+		});
+	}
+
+	#[test]
+	fn allow_callers() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_allow_callers::<Test>());
+		});
+	}
+
+	#[test]
+	fn disallow_callers() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_disallow_callers::<Test>());
+		});
+	}
+
+	#[test]
+	fn defund() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_defund::<Test>());
+		});
+	}
+
+	#[test]
+	fn on_initialize() {
+		new_test_ext().execute_with(|| {
+			assert_ok!(test_benchmark_on_initialize::<Test>());
+		});
+	}
+}
@@ -0,0 +1,436 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Paymaster pallet
+//!
+//! The Paymaster pallet lets the owner of a contract pay the fees of the transactions calling
+//! it. Each sponsored contract has a sponsor address, funded from the EVM balance of anyone,
+//! and rules on which transactions it pays for: who calls, which functions, at which gas
+//! price, and how much each caller may spend in a quota period. Transactions which do not
+//! match the rules, or which the sponsor cannot afford, are paid by their sender.
+//!
+//! The pallet is plugged into `pallet_evm` as its `FeePayer`.
+
+// Ensure we're `no_std` when compiling for Wasm.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	decl_module, decl_storage, decl_error, decl_event, ensure, traits::Get, weights::Weight,
+	storage::{IterableStorageMap, IterableStorageDoubleMap},
+};
+use frame_system::{ensure_signed, ensure_root};
+use sp_std::prelude::*;
+use sp_core::{H160, U256};
+use sp_runtime::{
+	ModuleId, RuntimeDebug, DispatchError,
+	traits::{AccountIdConversion, Zero},
+};
+use pallet_evm::{ConvertAccountId, FeePayer};
+use codec::{Encode, Decode};
+use sha3::{Digest, Keccak256};
+
+#[cfg(all(feature = "std", test))]
+mod tests;
+
+#[cfg(all(feature = "std", test))]
+mod mock;
+
+mod benchmarking;
+
+/// Maximum number of allowed callers and spending records of unregistered contracts removed at
+/// the start of each block.
+pub const REMOVAL_LIMIT: u32 = 1_000;
+
+/// Rules on the transactions a sponsorship pays for.
+#[derive(Clone, Eq, PartialEq, Default, Encode, Decode, RuntimeDebug)]
+pub struct SponsorshipRules {
+	/// Only pay for the callers allowed with `allow_callers`, rather than for any caller.
+	pub restrict_callers: bool,
+	/// Function selectors to pay for, or any function if `None`.
+	pub selectors: Option<Vec<[u8; 4]>>,
+	/// Maximum gas price to pay for, or any gas price if `None`.
+	pub max_gas_price: Option<U256>,
+	/// Maximum fee to pay for each caller in a quota period, or no maximum if `None`.
+	pub quota: Option<U256>,
+}
+
+/// Sponsorship of the transactions calling a contract.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub struct Sponsorship<AccountId> {
+	/// Owner of the contract, who sets the rules and may withdraw the funds.
+	pub owner: AccountId,
+	/// Rules on the transactions paid for.
+	pub rules: SponsorshipRules,
+}
+
+pub trait Trait: pallet_evm::Trait {
+	/// The overarching event type.
+	type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
+	/// The paymaster's module id, from which the sponsor address of each contract is derived.
+	type PaymasterModuleId: Get<ModuleId>;
+	/// Length in blocks of the periods over which quotas apply.
+	type QuotaPeriod: Get<Self::BlockNumber>;
+	/// Weight information for the extrinsics and hooks of this module.
+	type WeightInfo: WeightInfo;
+}
+
+/// Weight functions of the extrinsics and hooks of this module.
+pub trait WeightInfo {
+	fn register() -> Weight;
+	fn force_register() -> Weight;
+	fn unregister() -> Weight;
+	fn set_rules(s: u32, ) -> Weight;
+	fn allow_callers(c: u32, ) -> Weight;
+	fn disallow_callers(c: u32, ) -> Weight;
+	fn fund() -> Weight;
+	fn defund() -> Weight;
+	fn on_initialize(r: u32, ) -> Weight;
+}
+
+impl WeightInfo for () {
+	fn register() -> Weight { 1_000_000_000 }
+	fn force_register() -> Weight { 1_000_000_000 }
+	fn unregister() -> Weight { 1_000_000_000 }
+	fn set_rules(s: u32, ) -> Weight { 1_000_000_000u64.saturating_add((s as Weight).saturating_mul(100_000)) }
+	fn allow_callers(c: u32, ) -> Weight { 1_000_000_000u64.saturating_add((c as Weight).saturating_mul(25_000_000)) }
+	fn disallow_callers(c: u32, ) -> Weight { 1_000_000_000u64.saturating_add((c as Weight).saturating_mul(25_000_000)) }
+	fn fund() -> Weight { 1_000_000_000 }
+	fn defund() -> Weight { 1_000_000_000 }
+	fn on_initialize(r: u32, ) -> Weight { 10_000_000u64.saturating_add((r as Weight).saturating_mul(25_000_000)) }
+}
+
+decl_storage! {
+	trait Store for Module<T: Trait> as Paymaster {
+		/// Sponsorship of each sponsored contract.
+		Sponsorships get(fn sponsorships): map hasher(blake2_128_concat) H160 => Option<Sponsorship<T::AccountId>>;
+		/// Callers allowed by the sponsorship of each contract, if it restricts callers.
+		AllowedCallers get(fn allowed_callers):
+			double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H160 => bool;
+		/// Fee paid by the sponsor of each contract for each caller, along with the start of the
+		/// quota period it was paid in.
+		Spent: double_map hasher(blake2_128_concat) H160, hasher(blake2_128_concat) H160 => (T::BlockNumber, U256);
+		/// Unregistered contracts whose allowed callers and spending records are yet to be
+		/// removed.
+		RemovalQueue: map hasher(blake2_128_concat) H160 => ();
+	}
+}
+
+decl_event! {
+	/// Paymaster events
+	pub enum Event<T> where
+		<T as frame_system::Trait>::AccountId,
+	{
+		/// A contract is sponsored, and owned by the account.
+		Registered(H160, AccountId),
+		/// A contract is no longer sponsored.
+		Unregistered(H160),
+		/// The rules of the sponsorship of a contract were changed.
+		RulesChanged(H160),
+		/// Callers were allowed or disallowed by the sponsorship of a contract.
+		CallersChanged(H160),
+		/// The sponsorship of a contract has been funded with the given value.
+		Funded(H160, U256),
+		/// The given value has been withdrawn from the sponsorship of a contract.
+		Defunded(H160, U256),
+		/// The sponsor of a contract has paid the given fee for a transaction from the caller.
+		Sponsored(H160, H160, U256),
+	}
+}
+
+decl_error! {
+	pub enum Error for Module<T: Trait> {
+		/// The contract is already sponsored.
+		AlreadyRegistered,
+		/// The contract is not sponsored.
+		NotRegistered,
+		/// The contract was not created by the sender with the given nonce.
+		NotCreator,
+		/// The sender does not own the sponsorship.
+		NotOwner,
+		/// The contract was unregistered, and its sponsorship is still being removed.
+		RemovalPending,
+	}
+}
+
+decl_module! {
+	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		type Error = Error<T>;
+
+		fn deposit_event() = default;
+
+		const PaymasterModuleId: ModuleId = T::PaymasterModuleId::get();
+
+		const QuotaPeriod: T::BlockNumber = T::QuotaPeriod::get();
+
+		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			let removed = Self::purge_removed(REMOVAL_LIMIT);
+			T::WeightInfo::on_initialize(removed)
+		}
+
+		/// Sponsor a contract created by the sender, with the given nonce, from the EVM address
+		/// of the sender. The sponsorship pays for nothing until it is funded.
+		#[weight = T::WeightInfo::register()]
+		fn register(origin, contract: H160, nonce: U256) {
+			let owner = ensure_signed(origin)?;
+			let creator = T::ConvertAccountId::convert_account_id(&owner);
+			ensure!(Self::create_address(creator, nonce) == contract, Error::<T>::NotCreator);
+
+			Self::do_register(contract, owner)?;
+		}
+
+		/// Sponsor a contract, owned by the given account.
+		#[weight = T::WeightInfo::force_register()]
+		fn force_register(origin, contract: H160, owner: T::AccountId) {
+			ensure_root(origin)?;
+
+			Self::do_register(contract, owner)?;
+		}
+
+		/// Stop sponsoring a contract, returning its funds to the EVM address of the owner. Its
+		/// allowed callers and spending records are removed a bounded number at a time at the
+		/// start of the next blocks, and it cannot be registered again until then.
+		#[weight = T::WeightInfo::unregister()
+			.saturating_add(pallet_evm::Module::<T>::commit_weight(2, 0))]
+		fn unregister(origin, contract: H160) {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::ensure_owner(&contract, sender)?;
+
+			let sponsor = Self::sponsor_address(&contract);
+			let funds = pallet_evm::Module::<T>::accounts(&sponsor).balance;
+			pallet_evm::Module::<T>::transfer(
+				&sponsor,
+				&T::ConvertAccountId::convert_account_id(&owner),
+				funds,
+			)?;
+
+			Sponsorships::<T>::remove(&contract);
+			RemovalQueue::insert(&contract, ());
+			Self::deposit_event(RawEvent::Unregistered(contract));
+		}
+
+		/// Set the rules on the transactions the sponsorship of a contract pays for.
+		#[weight = T::WeightInfo::set_rules(rules.selectors.as_ref().map_or(0, |s| s.len() as u32))]
+		fn set_rules(origin, contract: H160, rules: SponsorshipRules) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&contract, sender)?;
+
+			Sponsorships::<T>::mutate(&contract, |sponsorship| {
+				if let Some(sponsorship) = sponsorship {
+					sponsorship.rules = rules;
+				}
+			});
+			Self::deposit_event(RawEvent::RulesChanged(contract));
+		}
+
+		/// Allow callers, if the sponsorship of a contract restricts them.
+		#[weight = T::WeightInfo::allow_callers(callers.len() as u32)]
+		fn allow_callers(origin, contract: H160, callers: Vec<H160>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&contract, sender)?;
+
+			for caller in &callers {
+				AllowedCallers::insert(&contract, caller, true);
+			}
+			Self::deposit_event(RawEvent::CallersChanged(contract));
+		}
+
+		/// Disallow callers, if the sponsorship of a contract restricts them.
+		#[weight = T::WeightInfo::disallow_callers(callers.len() as u32)]
+		fn disallow_callers(origin, contract: H160, callers: Vec<H160>) {
+			let sender = ensure_signed(origin)?;
+			Self::ensure_owner(&contract, sender)?;
+
+			for caller in &callers {
+				AllowedCallers::remove(&contract, caller);
+			}
+			Self::deposit_event(RawEvent::CallersChanged(contract));
+		}
+
+		/// Fund the sponsorship of a contract from the EVM address of the sender.
+		#[weight = T::WeightInfo::fund()
+			.saturating_add(pallet_evm::Module::<T>::commit_weight(2, 0))]
+		fn fund(origin, contract: H160, value: U256) {
+			let sender = ensure_signed(origin)?;
+			ensure!(Sponsorships::<T>::contains_key(&contract), Error::<T>::NotRegistered);
+
+			pallet_evm::Module::<T>::transfer(
+				&T::ConvertAccountId::convert_account_id(&sender),
+				&Self::sponsor_address(&contract),
+				value,
+			)?;
+			Self::deposit_event(RawEvent::Funded(contract, value));
+		}
+
+		/// Withdraw funds of the sponsorship of a contract to the EVM address of the owner.
+		#[weight = T::WeightInfo::defund()
+			.saturating_add(pallet_evm::Module::<T>::commit_weight(2, 0))]
+		fn defund(origin, contract: H160, value: U256) {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::ensure_owner(&contract, sender)?;
+
+			pallet_evm::Module::<T>::transfer(
+				&Self::sponsor_address(&contract),
+				&T::ConvertAccountId::convert_account_id(&owner),
+				value,
+			)?;
+			Self::deposit_event(RawEvent::Defunded(contract, value));
+		}
+	}
+}
+
+impl<T: Trait> Module<T> {
+	/// Address holding the funds of the sponsorship of a contract, and paying its fees.
+	pub fn sponsor_address(contract: &H160) -> H160 {
+		let account_id: T::AccountId = T::PaymasterModuleId::get().into_sub_account(contract);
+		T::ConvertAccountId::convert_account_id(&account_id)
+	}
+
+	/// Fee paid by the sponsor of a contract for a caller in the current quota period.
+	pub fn spent(contract: &H160, caller: &H160) -> U256 {
+		let (start, spent) = Spent::<T>::get(contract, caller);
+		if start == Self::quota_period_start() {
+			spent
+		} else {
+			U256::zero()
+		}
+	}
+
+	/// Sponsor address paying for a transaction from `caller` to `contract`, if the
+	/// sponsorship of the contract matches the transaction and can afford `max_fee`.
+	pub fn sponsor(
+		caller: H160,
+		contract: H160,
+		input: &[u8],
+		gas_price: U256,
+		max_fee: U256,
+	) -> Option<H160> {
+		let rules = Self::sponsorships(&contract)?.rules;
+		if rules.restrict_callers && !AllowedCallers::get(&contract, &caller) {
+			return None
+		}
+		if let Some(selectors) = rules.selectors {
+			if input.len() < 4 || !selectors.iter().any(|selector| selector[..] == input[..4]) {
+				return None
+			}
+		}
+		if rules.max_gas_price.map_or(false, |max_gas_price| gas_price > max_gas_price) {
+			return None
+		}
+		if let Some(quota) = rules.quota {
+			if Self::spent(&contract, &caller).saturating_add(max_fee) > quota {
+				return None
+			}
+		}
+
+		let sponsor = Self::sponsor_address(&contract);
+		if pallet_evm::Module::<T>::accounts(&sponsor).balance < max_fee {
+			return None
+		}
+		Some(sponsor)
+	}
+
+	fn do_register(contract: H160, owner: T::AccountId) -> Result<(), DispatchError> {
+		ensure!(!Sponsorships::<T>::contains_key(&contract), Error::<T>::AlreadyRegistered);
+		ensure!(!RemovalQueue::contains_key(&contract), Error::<T>::RemovalPending);
+
+		Sponsorships::<T>::insert(&contract, Sponsorship {
+			owner: owner.clone(),
+			rules: SponsorshipRules::default(),
+		});
+		Self::deposit_event(RawEvent::Registered(contract, owner));
+		Ok(())
+	}
+
+	/// Remove at most `limit` allowed callers and spending records of unregistered contracts.
+	/// Return the number removed.
+	fn purge_removed(limit: u32) -> u32 {
+		let mut remaining = limit as usize;
+
+		while remaining > 0 {
+			let contract = match RemovalQueue::iter().next() {
+				Some((contract, ())) => contract,
+				None => break,
+			};
+			remaining -= AllowedCallers::drain_prefix(&contract).take(remaining).count();
+			if remaining > 0 {
+				remaining -= Spent::<T>::drain_prefix(&contract).take(remaining).count();
+			}
+
+			// Fewer entries than allowed were left, so all of them are removed.
+			if remaining > 0 {
+				RemovalQueue::remove(&contract);
+			}
+		}
+
+		limit - remaining as u32
+	}
+
+	fn ensure_owner(contract: &H160, sender: T::AccountId) -> Result<T::AccountId, DispatchError> {
+		let sponsorship = Self::sponsorships(contract).ok_or(Error::<T>::NotRegistered)?;
+		ensure!(sponsorship.owner == sender, Error::<T>::NotOwner);
+		Ok(sender)
+	}
+
+	fn quota_period_start() -> T::BlockNumber {
+		let number = frame_system::Module::<T>::block_number();
+		let period = T::QuotaPeriod::get();
+		if period.is_zero() {
+			Zero::zero()
+		} else {
+			number - number % period
+		}
+	}
+
+	/// Address of a contract created by `creator` with `nonce`, as by `CREATE`.
+	fn create_address(creator: H160, nonce: U256) -> H160 {
+		let mut stream = rlp::RlpStream::new_list(2);
+		stream.append(&creator);
+		stream.append(&nonce);
+		H160::from_slice(&Keccak256::digest(&stream.out())[12..])
+	}
+}
+
+impl<T: Trait> FeePayer for Module<T> {
+	fn fee_payer(
+		source: H160,
+		target: Option<H160>,
+		input: &[u8],
+		gas_price: U256,
+		max_fee: U256,
+	) -> H160 {
+		target
+			.and_then(|contract| Self::sponsor(source, contract, input, gas_price, max_fee))
+			.unwrap_or(source)
+	}
+
+	fn on_fee_paid(payer: H160, source: H160, target: Option<H160>, fee: U256) {
+		let contract = match target {
+			Some(contract) if payer != source && payer == Self::sponsor_address(&contract) =>
+				contract,
+			_ => return,
+		};
+
+		let period_start = Self::quota_period_start();
+		Spent::<T>::mutate(&contract, &source, |(start, spent)| {
+			if *start != period_start {
+				*start = period_start;
+				*spent = U256::zero();
+			}
+			*spent = spent.saturating_add(fee);
+		});
+		Self::deposit_event(RawEvent::Sponsored(contract, source, fee));
+	}
+}
@@ -0,0 +1,184 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test utilities
+
+use super::*;
+use crate::{Module, Trait};
+use std::collections::BTreeMap;
+use frame_support::{impl_outer_origin, parameter_types, weights::Weight};
+use pallet_evm::{FeeCalculator, GenesisAccount, HashTruncateConvertAccountId};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+impl_outer_origin! {
+	pub enum Origin for Test where system = frame_system {}
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+impl frame_system::Trait for Test {
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type Origin = Origin;
+	type Call = ();
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = H256;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = ();
+	type BlockHashCount = BlockHashCount;
+	type MaximumBlockWeight = MaximumBlockWeight;
+	type DbWeight = ();
+	type BlockExecutionWeight = ();
+	type ExtrinsicBaseWeight = ();
+	type MaximumExtrinsicWeight = MaximumBlockWeight;
+	type MaximumBlockLength = MaximumBlockLength;
+	type AvailableBlockRatio = AvailableBlockRatio;
+	type Version = ();
+	type ModuleToIndex = ();
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Trait for Test {
+	type Balance = u64;
+	type Event = ();
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 6000 / 2;
+}
+
+impl pallet_timestamp::Trait for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+pub struct FixedGasPrice;
+impl FeeCalculator for FixedGasPrice {
+	fn min_gas_price() -> U256 {
+		1.into()
+	}
+}
+
+parameter_types! {
+	pub const ChainId: u64 = 42;
+	pub BlockGasLimit: U256 = U256::from(15_000_000);
+	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
+	pub const PaymasterModuleId: ModuleId = ModuleId(*b"py/paymr");
+	pub const QuotaPeriod: u64 = 100;
+}
+
+impl pallet_evm::Trait for Test {
+	type ModuleId = EVMModuleId;
+	type FeeCalculator = FixedGasPrice;
+	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
+	type Currency = Balances;
+	type Event = ();
+	type Precompiles = ();
+	type ChainId = ChainId;
+	type BlockGasLimit = BlockGasLimit;
+	type FindAuthor = ();
+	type OnChargeTransaction = ();
+	type FeePayer = Paymaster;
+	type AccessControl = ();
+	type EmergencyOrigin = frame_system::EnsureRoot<H256>;
+	type WeightInfo = ();
+}
+
+impl Trait for Test {
+	type Event = ();
+	type PaymasterModuleId = PaymasterModuleId;
+	type QuotaPeriod = QuotaPeriod;
+	type WeightInfo = ();
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+pub type Evm = pallet_evm::Module<Test>;
+pub type Paymaster = Module<Test>;
+
+/// Substrate account owning the sponsored contract.
+pub fn owner() -> H256 {
+	H256::repeat_byte(1)
+}
+
+/// EVM address of a Substrate account.
+pub fn evm_address(account_id: &H256) -> H160 {
+	HashTruncateConvertAccountId::<BlakeTwo256>::convert_account_id(account_id)
+}
+
+/// Callers of the sponsored contract, without any balance.
+pub fn caller(seed: u8) -> H160 {
+	H160::repeat_byte(seed)
+}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	let mut accounts = BTreeMap::new();
+	accounts.insert(evm_address(&owner()), GenesisAccount {
+		nonce: U256::zero(),
+		balance: U256::from(10_000_000),
+		storage: Default::default(),
+		code: Vec::new(),
+	});
+	pallet_evm::GenesisConfig { accounts, frozen: Default::default() }.assimilate_storage(&mut t).unwrap();
+
+	let mut ext: sp_io::TestExternalities = t.into();
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Deploy a contract which does nothing from the owner, and return its address.
+pub fn deploy_contract() -> H160 {
+	// Deploys `STOP` as the contract code.
+	let init = vec![0x60, 0x00, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3];
+	Evm::execute_create(
+		evm_address(&owner()),
+		init,
+		U256::zero(),
+		1_000_000,
+		U256::one(),
+		None,
+		true,
+	).unwrap().value
+}
@@ -0,0 +1,219 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Paymaster pallet tests
+
+use super::*;
+use mock::*;
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
+use pallet_evm::ExecutionInfo;
+use sp_core::H256;
+
+/// Selector of `transfer(address,uint256)`, costing 64 gas as call data.
+const TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+fn register_contract() -> H160 {
+	let contract = deploy_contract();
+	assert_ok!(Paymaster::register(Origin::signed(owner()), contract, U256::zero()));
+	contract
+}
+
+fn call(
+	caller: H160,
+	contract: H160,
+	gas_limit: u32,
+) -> Result<ExecutionInfo<Vec<u8>>, pallet_evm::Error<Test>> {
+	Evm::execute_call(
+		caller,
+		contract,
+		TRANSFER.to_vec(),
+		U256::zero(),
+		gas_limit,
+		U256::one(),
+		None,
+		true,
+	)
+}
+
+#[test]
+fn contracts_are_registered_by_their_creator() {
+	new_test_ext().execute_with(|| {
+		let contract = deploy_contract();
+
+		assert_noop!(
+			Paymaster::register(Origin::signed(owner()), contract, U256::one()),
+			Error::<Test>::NotCreator,
+		);
+		assert_noop!(
+			Paymaster::register(Origin::signed(H256::repeat_byte(2)), contract, U256::zero()),
+			Error::<Test>::NotCreator,
+		);
+
+		assert_ok!(Paymaster::register(Origin::signed(owner()), contract, U256::zero()));
+		assert_eq!(Paymaster::sponsorships(&contract).unwrap().owner, owner());
+		assert_noop!(
+			Paymaster::register(Origin::signed(owner()), contract, U256::zero()),
+			Error::<Test>::AlreadyRegistered,
+		);
+
+		assert_ok!(Paymaster::force_register(Origin::root(), caller(9), owner()));
+		assert_eq!(Paymaster::sponsorships(&caller(9)).unwrap().owner, owner());
+	});
+}
+
+#[test]
+fn sponsored_transactions_are_paid_by_the_sponsor() {
+	new_test_ext().execute_with(|| {
+		let contract = register_contract();
+		let sponsor = Paymaster::sponsor_address(&contract);
+
+		// Neither the caller nor the unfunded sponsor can pay.
+		assert!(call(caller(1), contract, 50_000).is_err());
+
+		assert_ok!(Paymaster::fund(Origin::signed(owner()), contract, U256::from(1_000_000)));
+		let info = call(caller(1), contract, 50_000).unwrap();
+
+		assert_eq!(info.fee_payer, sponsor);
+		assert_eq!(info.used_gas, U256::from(21_064));
+		assert_eq!(Evm::accounts(&sponsor).balance, U256::from(1_000_000 - 21_064));
+		assert_eq!(Evm::accounts(&caller(1)).balance, U256::zero());
+		assert_eq!(Paymaster::spent(&contract, &caller(1)), U256::from(21_064));
+	});
+}
+
+#[test]
+fn exhausted_quotas_fall_back_to_the_sender() {
+	new_test_ext().execute_with(|| {
+		let contract = register_contract();
+		let sponsor = Paymaster::sponsor_address(&contract);
+		assert_ok!(Paymaster::set_rules(Origin::signed(owner()), contract, SponsorshipRules {
+			quota: Some(U256::from(100_000)),
+			..Default::default()
+		}));
+		assert_ok!(Paymaster::fund(Origin::signed(owner()), contract, U256::from(1_000_000)));
+
+		// A transaction is sponsored only if its whole gas limit fits in the quota left.
+		for _ in 0..3 {
+			assert_eq!(call(caller(1), contract, 50_000).unwrap().fee_payer, sponsor);
+		}
+		assert_eq!(Paymaster::spent(&contract, &caller(1)), U256::from(3 * 21_064));
+		assert_eq!(Evm::fee_payer(caller(1), Some(contract), &TRANSFER, 50_000, U256::one()), caller(1));
+		assert!(call(caller(1), contract, 50_000).is_err());
+
+		assert_eq!(call(caller(1), contract, 30_000).unwrap().fee_payer, sponsor);
+		assert_eq!(Paymaster::spent(&contract, &caller(1)), U256::from(4 * 21_064));
+		assert!(call(caller(1), contract, 30_000).is_err());
+
+		// Each caller has its own quota.
+		assert_eq!(call(caller(2), contract, 50_000).unwrap().fee_payer, sponsor);
+
+		// Quotas are renewed every period.
+		System::set_block_number(QuotaPeriod::get());
+		assert_eq!(Paymaster::spent(&contract, &caller(1)), U256::zero());
+		assert_eq!(call(caller(1), contract, 50_000).unwrap().fee_payer, sponsor);
+		assert_eq!(Paymaster::spent(&contract, &caller(1)), U256::from(21_064));
+	});
+}
+
+#[test]
+fn rules_select_sponsored_transactions() {
+	new_test_ext().execute_with(|| {
+		let contract = register_contract();
+		let sponsor = Paymaster::sponsor_address(&contract);
+		assert_ok!(Paymaster::set_rules(Origin::signed(owner()), contract, SponsorshipRules {
+			restrict_callers: true,
+			selectors: Some(vec![TRANSFER]),
+			max_gas_price: Some(U256::from(10)),
+			quota: None,
+		}));
+		assert_ok!(Paymaster::allow_callers(Origin::signed(owner()), contract, vec![caller(1)]));
+		assert_ok!(Paymaster::fund(Origin::signed(owner()), contract, U256::from(1_000_000)));
+
+		let fee_payer = |caller: H160, input: &[u8], gas_price: u64| {
+			Evm::fee_payer(caller, Some(contract), input, 50_000, U256::from(gas_price))
+		};
+		assert_eq!(fee_payer(caller(1), &TRANSFER, 10), sponsor);
+		assert_eq!(fee_payer(caller(2), &TRANSFER, 10), caller(2));
+		assert_eq!(fee_payer(caller(1), &[0x09, 0x5e, 0xa7, 0xb3], 10), caller(1));
+		assert_eq!(fee_payer(caller(1), &[], 10), caller(1));
+		assert_eq!(fee_payer(caller(1), &TRANSFER, 11), caller(1));
+		// Contract creations are paid by their sender.
+		assert_eq!(Evm::fee_payer(caller(1), None, &TRANSFER, 50_000, U256::one()), caller(1));
+
+		assert_ok!(Paymaster::disallow_callers(Origin::signed(owner()), contract, vec![caller(1)]));
+		assert_eq!(fee_payer(caller(1), &TRANSFER, 10), caller(1));
+
+		assert_noop!(
+			Paymaster::set_rules(Origin::signed(H256::repeat_byte(2)), contract, Default::default()),
+			Error::<Test>::NotOwner,
+		);
+	});
+}
+
+#[test]
+fn funds_are_returned_to_the_owner() {
+	new_test_ext().execute_with(|| {
+		let contract = register_contract();
+		let sponsor = Paymaster::sponsor_address(&contract);
+		let owner_address = evm_address(&owner());
+		let balance = Evm::accounts(&owner_address).balance;
+
+		assert_ok!(Paymaster::fund(Origin::signed(owner()), contract, U256::from(1_000_000)));
+		assert_eq!(Evm::accounts(&owner_address).balance, balance - U256::from(1_000_000));
+
+		assert_ok!(Paymaster::defund(Origin::signed(owner()), contract, U256::from(400_000)));
+		assert_eq!(Evm::accounts(&sponsor).balance, U256::from(600_000));
+		assert_noop!(
+			Paymaster::defund(Origin::signed(H256::repeat_byte(2)), contract, U256::from(1)),
+			Error::<Test>::NotOwner,
+		);
+
+		assert_ok!(Paymaster::unregister(Origin::signed(owner()), contract));
+		assert_eq!(Evm::accounts(&sponsor).balance, U256::zero());
+		assert_eq!(Evm::accounts(&owner_address).balance, balance);
+		assert_eq!(Paymaster::sponsorships(&contract), None);
+		assert_eq!(Evm::fee_payer(caller(1), Some(contract), &TRANSFER, 50_000, U256::one()), caller(1));
+	});
+}
+
+#[test]
+fn unregistered_sponsorships_are_removed_over_blocks() {
+	new_test_ext().execute_with(|| {
+		let contract = register_contract();
+		let callers = (0 .. REMOVAL_LIMIT as u64 + 1).map(H160::from_low_u64_be).collect::<Vec<_>>();
+		assert_ok!(Paymaster::allow_callers(Origin::signed(owner()), contract, callers));
+		Spent::<Test>::insert(&contract, &caller(1), (1, U256::one()));
+
+		assert_ok!(Paymaster::unregister(Origin::signed(owner()), contract));
+		assert!(RemovalQueue::contains_key(&contract));
+		assert_noop!(
+			Paymaster::register(Origin::signed(owner()), contract, U256::zero()),
+			Error::<Test>::RemovalPending,
+		);
+
+		assert_eq!(Paymaster::on_initialize(2), <() as WeightInfo>::on_initialize(REMOVAL_LIMIT));
+		assert_eq!(AllowedCallers::iter_prefix(&contract).count(), 1);
+		assert!(RemovalQueue::contains_key(&contract));
+
+		assert_eq!(Paymaster::on_initialize(3), <() as WeightInfo>::on_initialize(2));
+		assert_eq!(AllowedCallers::iter_prefix(&contract).count(), 0);
+		assert_eq!(Spent::<Test>::iter_prefix(&contract).count(), 0);
+		assert!(!RemovalQueue::contains_key(&contract));
+
+		assert_eq!(Paymaster::on_initialize(4), <() as WeightInfo>::on_initialize(0));
+		assert_ok!(Paymaster::register(Origin::signed(owner()), contract, U256::zero()));
+	});
+}
@@ -0,0 +1,130 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::weights::{DispatchInfo, GetDispatchInfo};
+use sp_runtime::{
+	traits::{
+		Applyable, Dispatchable, DispatchInfoOf, MaybeDisplay, Member, PostDispatchInfoOf,
+		SignedExtension, ValidateUnsigned,
+	},
+	transaction_validity::{
+		InvalidTransaction, TransactionSource, TransactionValidity, TransactionValidityError,
+	},
+	ApplyExtrinsicResultWithInfo, RuntimeDebug,
+};
+use crate::SelfContainedCall;
+
+/// Who an extrinsic is checked to be from.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum CheckedSignature<AccountId, Extra, SelfContainedSignedInfo> {
+	/// Signed by an account, with its signed extensions.
+	Signed(AccountId, Extra),
+	/// Not signed.
+	Unsigned,
+	/// Signed by the call itself.
+	SelfContained(SelfContainedSignedInfo),
+}
+
+/// An extrinsic which has passed the verification stage, as
+/// `sp_runtime::generic::CheckedExtrinsic` but possibly with a self-contained call.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct CheckedExtrinsic<AccountId, Call, Extra, SelfContainedSignedInfo> {
+	/// Who this purports to be from.
+	pub signed: CheckedSignature<AccountId, Extra, SelfContainedSignedInfo>,
+
+	/// The function that should be called.
+	pub function: Call,
+}
+
+impl<AccountId, Call, Extra, SelfContainedSignedInfo, Origin> Applyable
+	for CheckedExtrinsic<AccountId, Call, Extra, SelfContainedSignedInfo>
+where
+	AccountId: Member + MaybeDisplay,
+	Call: Member + Dispatchable<Origin=Origin> + SelfContainedCall<SignedInfo=SelfContainedSignedInfo>,
+	Extra: SignedExtension<AccountId=AccountId, Call=Call>,
+	Origin: From<Option<AccountId>>,
+	SelfContainedSignedInfo: Send + Sync,
+{
+	type Call = Call;
+
+	fn validate<U: ValidateUnsigned<Call = Self::Call>>(
+		&self,
+		source: TransactionSource,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		match self.signed {
+			CheckedSignature::Signed(ref id, ref extra) => {
+				Extra::validate(extra, id, &self.function, info, len)
+			},
+			CheckedSignature::Unsigned => {
+				let valid = Extra::validate_unsigned(&self.function, info, len)?;
+				let unsigned_validation = U::validate_unsigned(source, &self.function)?;
+				Ok(valid.combine_with(unsigned_validation))
+			},
+			CheckedSignature::SelfContained(ref signed_info) => {
+				let valid = Extra::validate_unsigned(&self.function, info, len)?;
+				let self_contained_validation = self.function
+					.validate_self_contained(signed_info)
+					.ok_or(TransactionValidityError::Invalid(InvalidTransaction::BadProof))??;
+				Ok(valid.combine_with(self_contained_validation))
+			},
+		}
+	}
+
+	fn apply<U: ValidateUnsigned<Call = Self::Call>>(
+		self,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> ApplyExtrinsicResultWithInfo<PostDispatchInfoOf<Self::Call>> {
+		let (pre, res) = match self.signed {
+			CheckedSignature::Signed(id, extra) => {
+				let pre = Extra::pre_dispatch(extra, &id, &self.function, info, len)?;
+				(pre, self.function.dispatch(Origin::from(Some(id))))
+			},
+			CheckedSignature::Unsigned => {
+				let pre = Extra::pre_dispatch_unsigned(&self.function, info, len)?;
+				U::pre_dispatch(&self.function)?;
+				(pre, self.function.dispatch(Origin::from(None::<AccountId>)))
+			},
+			CheckedSignature::SelfContained(signed_info) => {
+				self.function.pre_dispatch_self_contained(&signed_info)
+					.ok_or(TransactionValidityError::Invalid(InvalidTransaction::BadProof))??;
+				let pre = Extra::pre_dispatch_unsigned(&self.function, info, len)?;
+				let res = self.function.apply_self_contained(signed_info)
+					.ok_or(TransactionValidityError::Invalid(InvalidTransaction::BadProof))?;
+				(pre, res)
+			},
+		};
+		let post_info = match res {
+			Ok(info) => info,
+			Err(err) => err.post_info,
+		};
+		Extra::post_dispatch(pre, info, &post_info, len, &res.map(|_| ()).map_err(|e| e.error))?;
+		Ok(res)
+	}
+}
+
+impl<AccountId, Call, Extra, SelfContainedSignedInfo> GetDispatchInfo
+	for CheckedExtrinsic<AccountId, Call, Extra, SelfContainedSignedInfo>
+where
+	Call: GetDispatchInfo,
+{
+	fn get_dispatch_info(&self) -> DispatchInfo {
+		self.function.get_dispatch_info()
+	}
+}
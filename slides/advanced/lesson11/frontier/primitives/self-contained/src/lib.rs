@@ -0,0 +1,65 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extrinsics whose calls may carry their own signature, such as Ethereum transactions.
+//!
+//! An unsigned extrinsic with a self-contained call is checked by the call itself, and is then
+//! dispatched from the origin the call recovers. The pool and the block builder see it as a
+//! signed transaction, and it still goes through the signed extensions for unsigned
+//! extrinsics, so that its weight is accounted for like any other extrinsic.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod checked_extrinsic;
+mod unchecked_extrinsic;
+
+pub use crate::checked_extrinsic::{CheckedExtrinsic, CheckedSignature};
+pub use crate::unchecked_extrinsic::UncheckedExtrinsic;
+
+use sp_runtime::{
+	traits::{Dispatchable, PostDispatchInfoOf},
+	transaction_validity::{TransactionValidity, TransactionValidityError},
+	DispatchResultWithInfo,
+};
+
+/// A call which may carry its own signature. Every method returns `None` for calls which are
+/// not self-contained.
+pub trait SelfContainedCall: Dispatchable {
+	/// What a self-contained call is checked to be signed by.
+	type SignedInfo;
+
+	/// Whether the call carries its own signature.
+	fn is_self_contained(&self) -> bool;
+
+	/// Check the signature of the call, returning what it is signed by.
+	fn check_self_contained(&self) -> Option<Result<Self::SignedInfo, TransactionValidityError>>;
+
+	/// Validate the call for the transaction pool.
+	fn validate_self_contained(&self, info: &Self::SignedInfo) -> Option<TransactionValidity>;
+
+	/// Validate the call right before it is dispatched in a block.
+	fn pre_dispatch_self_contained(
+		&self,
+		info: &Self::SignedInfo,
+	) -> Option<Result<(), TransactionValidityError>>;
+
+	/// Dispatch the call from the origin of what it is signed by.
+	fn apply_self_contained(
+		self,
+		info: Self::SignedInfo,
+	) -> Option<DispatchResultWithInfo<PostDispatchInfoOf<Self>>>;
+}
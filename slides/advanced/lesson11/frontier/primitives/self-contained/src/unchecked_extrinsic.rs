@@ -0,0 +1,229 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_std::{ops::Deref, prelude::*};
+use codec::{Decode, Encode, EncodeLike, Error, Input};
+use frame_support::weights::{DispatchInfo, GetDispatchInfo};
+use sp_runtime::{
+	generic,
+	traits::{
+		self, Checkable, Extrinsic, ExtrinsicMetadata, IdentifyAccount, MaybeDisplay, Member,
+		SignedExtension,
+	},
+	transaction_validity::{InvalidTransaction, TransactionValidityError},
+	RuntimeDebug,
+};
+use crate::{CheckedExtrinsic, CheckedSignature, SelfContainedCall};
+
+/// An extrinsic right from the external world, as `sp_runtime::generic::UncheckedExtrinsic`
+/// with the same encoding, except that unsigned extrinsics with a self-contained call are
+/// checked by the call.
+#[derive(PartialEq, Eq, Clone, RuntimeDebug)]
+pub struct UncheckedExtrinsic<Address, Call, Signature, Extra: SignedExtension>(
+	pub generic::UncheckedExtrinsic<Address, Call, Signature, Extra>,
+);
+
+impl<Address, Call, Signature, Extra: SignedExtension>
+	UncheckedExtrinsic<Address, Call, Signature, Extra>
+{
+	/// New instance of a signed extrinsic aka "transaction".
+	pub fn new_signed(
+		function: Call,
+		signed: Address,
+		signature: Signature,
+		extra: Extra,
+	) -> Self {
+		Self(generic::UncheckedExtrinsic::new_signed(function, signed, signature, extra))
+	}
+
+	/// New instance of an unsigned extrinsic, either an "inherent" or a self-contained call.
+	pub fn new_unsigned(function: Call) -> Self {
+		Self(generic::UncheckedExtrinsic::new_unsigned(function))
+	}
+}
+
+// `construct_runtime!` reads the `function` of extrinsics when checking inherents.
+impl<Address, Call, Signature, Extra: SignedExtension> Deref
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+{
+	type Target = generic::UncheckedExtrinsic<Address, Call, Signature, Extra>;
+
+	fn deref(&self) -> &Self::Target {
+		&self.0
+	}
+}
+
+#[cfg(feature = "std")]
+impl<Address, Call, Signature, Extra> parity_util_mem::MallocSizeOf
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Extra: SignedExtension
+{
+	fn size_of(&self, _ops: &mut parity_util_mem::MallocSizeOfOps) -> usize {
+		// Instantiated only in runtime.
+		0
+	}
+}
+
+impl<Address, Call, Signature, Extra> Extrinsic
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Call: SelfContainedCall,
+	Extra: SignedExtension,
+{
+	type Call = Call;
+
+	type SignaturePayload = (
+		Address,
+		Signature,
+		Extra,
+	);
+
+	fn is_signed(&self) -> Option<bool> {
+		if self.0.function.is_self_contained() {
+			Some(true)
+		} else {
+			self.0.is_signed()
+		}
+	}
+
+	fn new(function: Call, signed_data: Option<Self::SignaturePayload>) -> Option<Self> {
+		generic::UncheckedExtrinsic::new(function, signed_data).map(Self)
+	}
+}
+
+impl<Address, AccountId, Call, Signature, Extra, Lookup>
+	Checkable<Lookup>
+for
+	UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Address: Member + MaybeDisplay,
+	Call: Encode + Member + SelfContainedCall,
+	Signature: Member + traits::Verify,
+	<Signature as traits::Verify>::Signer: IdentifyAccount<AccountId=AccountId>,
+	Extra: SignedExtension<AccountId=AccountId>,
+	AccountId: Member + MaybeDisplay,
+	Lookup: traits::Lookup<Source=Address, Target=AccountId>,
+{
+	type Checked = CheckedExtrinsic<
+		AccountId,
+		Call,
+		Extra,
+		<Call as SelfContainedCall>::SignedInfo,
+	>;
+
+	fn check(self, lookup: &Lookup) -> Result<Self::Checked, TransactionValidityError> {
+		if self.0.function.is_self_contained() {
+			// Self-contained calls carry their own signature, and no other.
+			if self.0.signature.is_some() {
+				return Err(InvalidTransaction::BadProof.into())
+			}
+
+			let signed_info = self.0.function.check_self_contained()
+				.ok_or(TransactionValidityError::Invalid(InvalidTransaction::BadProof))??;
+			Ok(CheckedExtrinsic {
+				signed: CheckedSignature::SelfContained(signed_info),
+				function: self.0.function,
+			})
+		} else {
+			let checked = Checkable::<Lookup>::check(self.0, lookup)?;
+			Ok(CheckedExtrinsic {
+				signed: match checked.signed {
+					Some((id, extra)) => CheckedSignature::Signed(id, extra),
+					None => CheckedSignature::Unsigned,
+				},
+				function: checked.function,
+			})
+		}
+	}
+}
+
+impl<Address, Call, Signature, Extra> ExtrinsicMetadata
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Extra: SignedExtension,
+{
+	const VERSION: u8 =
+		<generic::UncheckedExtrinsic<Address, Call, Signature, Extra> as ExtrinsicMetadata>::VERSION;
+	type SignedExtensions = Extra;
+}
+
+impl<Address, Call, Signature, Extra> GetDispatchInfo
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Call: GetDispatchInfo,
+	Extra: SignedExtension,
+{
+	fn get_dispatch_info(&self) -> DispatchInfo {
+		self.0.function.get_dispatch_info()
+	}
+}
+
+impl<Address, Call, Signature, Extra> Decode
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Address: Decode,
+	Signature: Decode,
+	Call: Decode,
+	Extra: SignedExtension,
+{
+	fn decode<I: Input>(input: &mut I) -> Result<Self, Error> {
+		generic::UncheckedExtrinsic::decode(input).map(Self)
+	}
+}
+
+impl<Address, Call, Signature, Extra> Encode
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Address: Encode,
+	Signature: Encode,
+	Call: Encode,
+	Extra: SignedExtension,
+{
+	fn encode(&self) -> Vec<u8> {
+		self.0.encode()
+	}
+}
+
+impl<Address, Call, Signature, Extra> EncodeLike
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+where
+	Address: Encode,
+	Signature: Encode,
+	Call: Encode,
+	Extra: SignedExtension,
+{}
+
+#[cfg(feature = "std")]
+impl<Address: Encode, Signature: Encode, Call: Encode, Extra: SignedExtension> serde::Serialize
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+{
+	fn serialize<S>(&self, seq: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+		serde::Serialize::serialize(&self.0, seq)
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'a, Address: Decode, Signature: Decode, Call: Decode, Extra: SignedExtension> serde::Deserialize<'a>
+	for UncheckedExtrinsic<Address, Call, Signature, Extra>
+{
+	fn deserialize<D>(de: D) -> Result<Self, D::Error> where
+		D: serde::Deserializer<'a>,
+	{
+		serde::Deserialize::deserialize(de).map(Self)
+	}
+}
@@ -127,6 +127,10 @@ pub trait EthApi {
 	#[rpc(name = "eth_estimateGas")]
 	fn estimate_gas(&self, _: CallRequest, _: Option<BlockNumber>) -> Result<U256>;
 
+	/// Estimate the sponsor paying the fee of given transaction, if not its sender.
+	#[rpc(name = "eth_estimateSponsor")]
+	fn estimate_sponsor(&self, _: CallRequest, _: Option<BlockNumber>) -> Result<Option<H160>>;
+
 	/// Get transaction by its hash.
 	#[rpc(name = "eth_getTransactionByHash")]
 	fn transaction_by_hash(&self, _: H256) -> Result<Option<Transaction>>;
@@ -0,0 +1,46 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Evm development rpc interface, as used by Truffle and Hardhat test suites.
+use ethereum_types::{H256, U256};
+use jsonrpc_core::{BoxFuture, Result};
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_EvmApi::gen_server::EvmApi as EvmApiServer;
+
+/// Evm development rpc interface.
+#[rpc(server)]
+pub trait EvmApi {
+	/// Seals a new block, even if there are no pending transactions.
+	/// Returns the hash of the new block.
+	#[rpc(name = "evm_mine")]
+	fn mine(&self) -> BoxFuture<H256>;
+
+	/// Takes a snapshot of the current best block. Returns the snapshot
+	/// identifier to be passed to `evm_revert`.
+	#[rpc(name = "evm_snapshot")]
+	fn snapshot(&self) -> Result<U256>;
+
+	/// Reverts the chain to the state of the given snapshot. The snapshot,
+	/// and all snapshots taken after it, are consumed.
+	#[rpc(name = "evm_revert")]
+	fn revert(&self, _: U256) -> BoxFuture<bool>;
+
+	/// Moves the timestamp of subsequently sealed blocks forward by the given
+	/// number of seconds. Returns the total time adjustment in seconds.
+	#[rpc(name = "evm_increaseTime")]
+	fn increase_time(&self, _: u64) -> Result<u64>;
+}
@@ -19,11 +19,13 @@ pub mod types;
 mod eth;
 mod eth_pubsub;
 mod eth_signing;
+mod evm;
 mod net;
 mod web3;
 
 pub use eth::{EthApi, EthApiServer, EthFilterApi};
 pub use eth_pubsub::EthPubSubApi;
 pub use eth_signing::EthSigningApi;
+pub use evm::{EvmApi, EvmApiServer};
 pub use net::NetApi;
 pub use web3::Web3Api;
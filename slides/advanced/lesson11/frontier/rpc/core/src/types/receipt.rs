@@ -40,6 +40,9 @@ pub struct Receipt {
 	pub gas_used: Option<U256>,
 	/// Contract address
 	pub contract_address: Option<H160>,
+	/// Sponsor which paid the fee, if not the sender
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub sponsor: Option<H160>,
 	/// Logs
 	pub logs: Vec<Log>,
 	/// State Root
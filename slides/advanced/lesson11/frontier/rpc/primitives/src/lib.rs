@@ -34,6 +34,7 @@ pub struct TransactionStatus {
 	pub contract_address: Option<H160>,
 	pub logs: Vec<Log>,
 	pub logs_bloom: Bloom,
+	pub sponsor: Option<H160>,
 }
 
 impl Default for TransactionStatus {
@@ -46,6 +47,7 @@ impl Default for TransactionStatus {
 			contract_address: None,
 			logs: Vec::new(),
 			logs_bloom: Bloom::default(),
+			sponsor: None,
 		}
 	}
 }
@@ -59,6 +61,7 @@ sp_api::decl_runtime_apis! {
 		fn account_code_at(address: H160) -> Vec<u8>;
 		fn author() -> H160;
 		fn storage_at(address: H160, index: U256) -> H256;
+		fn account_proof(address: H160, storage_keys: Vec<H256>) -> Option<pallet_evm::AccountProof>;
 		fn call(
 			from: H160,
 			to: H160,
@@ -68,6 +71,13 @@ sp_api::decl_runtime_apis! {
 			gas_price: U256,
 			nonce: Option<U256>,
 		) -> Option<(Vec<u8>, U256)>;
+		fn fee_payer(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			gas_limit: U256,
+			gas_price: U256,
+		) -> H160;
 		fn block_by_number(number: u32) -> (Option<EthereumBlock>, Vec<Option<TransactionStatus>>);
 		fn block_transaction_count_by_number(number: u32) -> Option<U256>;
 		fn block_by_hash(hash: H256) -> Option<EthereumBlock>;
@@ -0,0 +1,239 @@
+// Copyright 2017-2020 Parity Technologies (UK) Ltd.
+// This file is part of Frontier.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Index of header blooms by sections of blocks, used by `eth_getLogs`.
+//!
+//! As in geth bloombits, a section keeps for each bit of the bloom the blocks having it set.
+//! Matching a filter against a section only reads the rows of the bits of its addresses and
+//! topics, instead of the header of every block.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use ethereum::LogFilter;
+use ethereum_types::{Bloom, H256};
+
+/// Number of blocks in a section.
+pub const SECTION_SIZE: u32 = 4096;
+
+/// Set of blocks of a section, one bit per block.
+type Blocks = [u64; WORDS];
+const WORDS: usize = (SECTION_SIZE / 64) as usize;
+
+/// Positions of the bits set in a bloom.
+fn bits(bloom: &Bloom) -> impl Iterator<Item=u16> + '_ {
+	bloom.as_bytes().iter().enumerate().flat_map(|(index, byte)| {
+		(0..8).filter(move |bit| byte & (1 << bit) != 0)
+			.map(move |bit| (index * 8 + bit) as u16)
+	})
+}
+
+/// Header blooms of the blocks of a section.
+pub struct BloomSection {
+	head: H256,
+	len: u32,
+	rows: BTreeMap<u16, Vec<u16>>,
+}
+
+impl BloomSection {
+	/// Index the header blooms of the blocks of a section, in order. `head` is the hash of
+	/// the last block, used to tell whether the section is still on the best chain.
+	pub fn new<'a, I: IntoIterator<Item=&'a Bloom>>(head: H256, blooms: I) -> Self {
+		let mut len = 0;
+		let mut rows = BTreeMap::<u16, Vec<u16>>::new();
+		for bloom in blooms.into_iter().take(SECTION_SIZE as usize) {
+			for bit in bits(bloom) {
+				rows.entry(bit).or_default().push(len as u16);
+			}
+			len += 1;
+		}
+
+		Self { head, len, rows }
+	}
+
+	/// Hash of the last block of the section.
+	pub fn head(&self) -> H256 {
+		self.head
+	}
+
+	fn row(&self, bit: u16) -> Blocks {
+		let mut blocks = [0; WORDS];
+		for offset in self.rows.get(&bit).into_iter().flatten() {
+			blocks[*offset as usize / 64] |= 1 << (offset % 64);
+		}
+		blocks
+	}
+
+	/// Offsets in the section of the blocks whose bloom may match the filter, in order.
+	pub fn candidates(&self, filter: &LogFilter) -> Vec<u32> {
+		let mut matched = [u64::max_value(); WORDS];
+		for group in filter.bloom_groups() {
+			let mut any = [0; WORDS];
+			for input in group {
+				let mut all = [u64::max_value(); WORDS];
+				for bit in bits(&input) {
+					let row = self.row(bit);
+					all.iter_mut().zip(row.iter()).for_each(|(all, row)| *all &= row);
+				}
+				any.iter_mut().zip(all.iter()).for_each(|(any, all)| *any |= all);
+			}
+			matched.iter_mut().zip(any.iter()).for_each(|(matched, any)| *matched &= any);
+		}
+
+		(0..self.len)
+			.filter(|offset| matched[*offset as usize / 64] & (1 << (offset % 64)) != 0)
+			.collect()
+	}
+}
+
+/// Maximum number of sections kept in memory. Past it, the least recently used section is
+/// dropped, and indexed again from its blocks the next time it is searched.
+pub const MAX_SECTIONS: usize = 64;
+
+/// Sections indexed so far, by section number, along with when they were last used.
+#[derive(Default)]
+struct Sections {
+	sections: BTreeMap<u32, (u64, Arc<BloomSection>)>,
+	clock: u64,
+}
+
+/// Most recently used sections, by section number.
+#[derive(Default)]
+pub struct BloomIndex {
+	sections: Mutex<Sections>,
+}
+
+impl BloomIndex {
+	/// Indexed section, if any.
+	pub fn section(&self, number: u32) -> Option<Arc<BloomSection>> {
+		let mut sections = self.sections.lock().expect("bloom index lock poisoned");
+		sections.clock += 1;
+		let clock = sections.clock;
+		sections.sections.get_mut(&number).map(|(used, section)| {
+			*used = clock;
+			section.clone()
+		})
+	}
+
+	/// Index a section, replacing the previous index of it, and dropping the least recently
+	/// used section if more than `MAX_SECTIONS` are indexed.
+	pub fn insert(&self, number: u32, section: BloomSection) -> Arc<BloomSection> {
+		let section = Arc::new(section);
+		let mut sections = self.sections.lock().expect("bloom index lock poisoned");
+		sections.clock += 1;
+		let clock = sections.clock;
+		sections.sections.insert(number, (clock, section.clone()));
+
+		if sections.sections.len() > MAX_SECTIONS {
+			let least_used = sections.sections.iter()
+				.min_by_key(|(_, (used, _))| *used)
+				.map(|(number, _)| *number);
+			if let Some(least_used) = least_used {
+				sections.sections.remove(&least_used);
+			}
+		}
+		section
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::{BloomInput, H160};
+
+	fn address(seed: u64) -> H160 {
+		H160::from_low_u64_be(seed)
+	}
+
+	fn topic(seed: u64) -> H256 {
+		H256::from_low_u64_be(seed)
+	}
+
+	fn bloom(addresses: &[u64], topics: &[u64]) -> Bloom {
+		let mut bloom = Bloom::default();
+		for seed in addresses {
+			bloom.accrue(BloomInput::Raw(address(*seed).as_bytes()));
+		}
+		for seed in topics {
+			bloom.accrue(BloomInput::Raw(topic(*seed).as_bytes()));
+		}
+		bloom
+	}
+
+	#[test]
+	fn candidates_match_any_input_of_every_group() {
+		let blooms = vec![
+			bloom(&[1], &[10]),
+			bloom(&[2], &[10]),
+			bloom(&[1], &[]),
+			bloom(&[], &[10]),
+			bloom(&[3], &[11]),
+			Bloom::default(),
+		];
+		let section = BloomSection::new(H256::zero(), &blooms);
+		let filter = LogFilter {
+			addresses: vec![address(1), address(2)],
+			topics: vec![vec![topic(10)]],
+		};
+
+		assert_eq!(section.candidates(&filter), vec![0, 1]);
+	}
+
+	#[test]
+	fn candidates_have_every_bit_of_an_input() {
+		let input = bloom(&[1], &[]);
+		let last_bit = bits(&input).last().unwrap();
+		let mut partial = Bloom::default();
+		for bit in bits(&input).filter(|bit| *bit != last_bit) {
+			partial.0[bit as usize / 8] |= 1 << (bit % 8);
+		}
+		let section = BloomSection::new(H256::zero(), &[input, partial, input | bloom(&[2], &[])]);
+		let filter = LogFilter { addresses: vec![address(1)], topics: Vec::new() };
+
+		assert_eq!(section.candidates(&filter), vec![0, 2]);
+	}
+
+	#[test]
+	fn empty_filter_matches_every_block() {
+		let section = BloomSection::new(H256::zero(), &[bloom(&[1], &[]), Bloom::default()]);
+
+		assert_eq!(section.candidates(&LogFilter::default()), vec![0, 1]);
+	}
+
+	#[test]
+	fn partial_sections_only_have_their_blocks() {
+		let mut blooms = vec![Bloom::default(); 70];
+		blooms[65] = bloom(&[1], &[10]);
+		let section = BloomSection::new(H256::zero(), &blooms);
+		let filter = LogFilter { addresses: vec![address(1)], topics: vec![vec![topic(10)]] };
+
+		assert_eq!(section.candidates(&filter), vec![65]);
+		assert_eq!(section.candidates(&LogFilter::default()), (0..70).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn least_recently_used_sections_are_dropped() {
+		let index = BloomIndex::default();
+		for number in 0..MAX_SECTIONS as u32 {
+			index.insert(number, BloomSection::new(H256::zero(), &[]));
+		}
+		assert!(index.section(0).is_some());
+
+		index.insert(MAX_SECTIONS as u32, BloomSection::new(H256::zero(), &[]));
+		assert!(index.section(0).is_some());
+		assert!(index.section(1).is_none());
+		assert!(index.section(MAX_SECTIONS as u32).is_some());
+	}
+}
@@ -14,9 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
+mod bloom_index;
+
 use std::{marker::PhantomData, sync::Arc};
 use std::collections::BTreeMap;
-use ethereum::{Block as EthereumBlock, Transaction as EthereumTransaction};
+use ethereum::{Block as EthereumBlock, Transaction as EthereumTransaction, LogFilter};
 use ethereum_types::{H160, H256, H64, U256, U64};
 use jsonrpc_core::{BoxFuture, Result, ErrorCode, Error, futures::future::{self, Future}};
 use futures::future::TryFutureExt;
@@ -31,9 +33,10 @@ use sp_runtime::traits::BlakeTwo256;
 use frontier_rpc_core::EthApi as EthApiT;
 use frontier_rpc_core::types::{
 	BlockNumber, Bytes, CallRequest, EthAccount, Filter, Index, Log, Receipt, RichBlock,
-	SyncStatus, Transaction, Work, Rich, Block, BlockTransactions
+	SyncStatus, Transaction, Work, Rich, Block, BlockTransactions, StorageProof, VariadicValue
 };
 use frontier_rpc_primitives::{EthereumRuntimeApi, ConvertTransaction, TransactionStatus};
+use crate::bloom_index::{BloomIndex, BloomSection, SECTION_SIZE};
 
 pub use frontier_rpc_core::EthApiServer;
 
@@ -51,6 +54,7 @@ pub struct EthApi<B: BlockT, C, SC, P, CT, BE> {
 	select_chain: SC,
 	convert_transaction: CT,
 	is_authority: bool,
+	bloom_index: Arc<BloomIndex>,
 	_marker: PhantomData<(B,BE)>,
 }
 
@@ -62,7 +66,15 @@ impl<B: BlockT, C, SC, P, CT, BE> EthApi<B, C, SC, P, CT, BE> {
 		convert_transaction: CT,
 		is_authority: bool
 	) -> Self {
-		Self { client, select_chain, pool, convert_transaction, is_authority, _marker: PhantomData }
+		Self {
+			client,
+			select_chain,
+			pool,
+			convert_transaction,
+			is_authority,
+			bloom_index: Arc::new(BloomIndex::default()),
+			_marker: PhantomData,
+		}
 	}
 }
 
@@ -163,6 +175,52 @@ fn transaction_build(
 	}
 }
 
+fn filter_build(filter: &Filter) -> LogFilter {
+	LogFilter {
+		addresses: match filter.address.clone() {
+			Some(VariadicValue::Single(address)) => vec![address],
+			Some(VariadicValue::Multiple(addresses)) => addresses,
+			_ => Vec::new(),
+		},
+		topics: filter.topics.clone().unwrap_or_default().into_iter().map(|topic| match topic {
+			VariadicValue::Single(topic) => vec![topic],
+			VariadicValue::Multiple(topics) => topics,
+			VariadicValue::Null => Vec::new(),
+		}).collect(),
+	}
+}
+
+fn filter_block_logs(
+	logs: &mut Vec<Log>,
+	filter: &LogFilter,
+	block: EthereumBlock,
+	statuses: Vec<Option<TransactionStatus>>
+) {
+	let block_hash = H256::from_slice(
+		Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+	);
+	let mut log_index = 0u32;
+	for status in statuses.into_iter().flatten() {
+		for (transaction_log_index, log) in status.logs.into_iter().enumerate() {
+			if filter.matches(&log) {
+				logs.push(Log {
+					address: log.address,
+					topics: log.topics,
+					data: Bytes(log.data),
+					block_hash: Some(block_hash),
+					block_number: Some(block.header.number),
+					transaction_hash: Some(status.transaction_hash),
+					transaction_index: Some(status.transaction_index.into()),
+					log_index: Some(U256::from(log_index)),
+					transaction_log_index: Some(U256::from(transaction_log_index)),
+					removed: false,
+				});
+			}
+			log_index += 1;
+		}
+	}
+}
+
 impl<B, C, SC, P, CT, BE> EthApi<B, C, SC, P, CT, BE> where
 	C: ProvideRuntimeApi<B> + StorageProvider<B,BE>,
 	C::Api: EthereumRuntimeApi<B>,
@@ -216,6 +274,106 @@ impl<B, C, SC, P, CT, BE> EthApi<B, C, SC, P, CT, BE> where
 		}
 		Ok(native_number)
 	}
+
+	/// Bloom index of a complete section, checked to still be on the best chain. The section
+	/// is indexed from its blocks if it is not yet, or was reorganised, and `build` is set.
+	fn bloom_section(
+		&self,
+		best: &BlockId<B>,
+		number: u32,
+		build: bool
+	) -> Result<Option<Arc<BloomSection>>> {
+		let first = number * SECTION_SIZE;
+		let last = first + SECTION_SIZE - 1;
+		let (block, _) = self.client.runtime_api().block_by_number(best, last)
+			.map_err(|_| internal_err("fetch runtime block failed"))?;
+		let head = block.map(|block| H256::from_slice(
+			Keccak256::digest(&rlp::encode(&block.header)).as_slice()
+		)).unwrap_or_default();
+
+		if let Some(section) = self.bloom_index.section(number) {
+			if section.head() == head {
+				return Ok(Some(section));
+			}
+		}
+		if !build {
+			return Ok(None);
+		}
+
+		let mut blooms = Vec::with_capacity(SECTION_SIZE as usize);
+		for block_number in first..=last {
+			let (block, _) = self.client.runtime_api().block_by_number(best, block_number)
+				.map_err(|_| internal_err("fetch runtime block failed"))?;
+			blooms.push(block.map(|block| block.header.logs_bloom).unwrap_or_default());
+		}
+		Ok(Some(self.bloom_index.insert(number, BloomSection::new(head, &blooms))))
+	}
+
+	fn filter_logs(&self, filter: Filter) -> Result<Vec<Log>> {
+		let header = self.select_chain.best_chain()
+			.map_err(|_| internal_err("fetch header failed"))?;
+		let best = BlockId::Hash(header.hash());
+		let best_number = UniqueSaturatedInto::<u32>::unique_saturated_into(header.number().clone());
+		let log_filter = filter_build(&filter);
+		let mut logs = Vec::new();
+
+		if let Some(hash) = filter.block_hash {
+			if let Ok((Some(block), statuses)) = self.client.runtime_api()
+				.block_by_hash_with_statuses(&best, hash)
+			{
+				filter_block_logs(&mut logs, &log_filter, block, statuses);
+			}
+		} else {
+			let from = self.native_block_number(filter.from_block.clone())?.unwrap_or(best_number);
+			let to = self.native_block_number(filter.to_block.clone())?
+				.unwrap_or(best_number)
+				.min(best_number);
+
+			let mut number = from;
+			while number <= to {
+				let section = number / SECTION_SIZE;
+				let first = section * SECTION_SIZE;
+				let last = first + (SECTION_SIZE - 1);
+
+				// Sections are only indexed once complete, and on demand when the whole
+				// section is searched, so that short ranges never load a whole section.
+				let indexed = if last <= best_number {
+					self.bloom_section(&best, section, number == first && last <= to)?
+				} else {
+					None
+				};
+				let candidates: Vec<u32> = match indexed {
+					Some(indexed) => indexed.candidates(&log_filter).into_iter()
+						.map(|offset| first + offset)
+						.filter(|candidate| *candidate >= number && *candidate <= to)
+						.collect(),
+					None => (number..=to.min(last)).collect(),
+				};
+
+				for candidate in candidates {
+					if let Ok((Some(block), statuses)) = self.client.runtime_api()
+						.block_by_number(&best, candidate)
+					{
+						if log_filter.matches_bloom(&block.header.logs_bloom) {
+							filter_block_logs(&mut logs, &log_filter, block, statuses);
+						}
+					}
+				}
+
+				if last >= to {
+					break;
+				}
+				number = last + 1;
+			}
+		}
+
+		if let Some(limit) = filter.limit {
+			if logs.len() > limit {
+				logs.drain(..logs.len() - limit);
+			}
+		}
+		Ok(logs)
+	}
 }
 
 impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
@@ -305,8 +463,36 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		Ok(U256::zero())
 	}
 
-	fn proof(&self, _: H160, _: Vec<H256>, _: Option<BlockNumber>) -> BoxFuture<EthAccount> {
-		unimplemented!("proof");
+	fn proof(&self, address: H160, keys: Vec<H256>, number: Option<BlockNumber>) -> BoxFuture<EthAccount> {
+		let native_number = match self.native_block_number(number) {
+			Ok(Some(native_number)) => native_number,
+			_ => return Box::new(
+				future::result(Err(internal_err("block number not found")))
+			),
+		};
+
+		let proof = match self.client.runtime_api()
+			.account_proof(&BlockId::Number(native_number.into()), address, keys)
+		{
+			Ok(Some(proof)) => proof,
+			_ => return Box::new(
+				future::result(Err(internal_err("fetch runtime account proof failed")))
+			),
+		};
+
+		Box::new(future::result(Ok(EthAccount {
+			address,
+			balance: proof.balance,
+			nonce: proof.nonce,
+			code_hash: proof.code_hash,
+			storage_hash: proof.storage_root,
+			account_proof: proof.account_proof.into_iter().map(Bytes).collect(),
+			storage_proof: proof.storage_proofs.into_iter().map(|storage| StorageProof {
+				key: U256::from_big_endian(storage.key.as_bytes()),
+				value: U256::from_big_endian(storage.value.as_bytes()),
+				proof: storage.proof.into_iter().map(Bytes).collect(),
+			}).collect(),
+		})))
 	}
 
 	fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> Result<H256> {
@@ -507,6 +693,31 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		Ok(used_gas)
 	}
 
+	fn estimate_sponsor(&self, request: CallRequest, _: Option<BlockNumber>) -> Result<Option<H160>> {
+		let header = self
+			.select_chain
+			.best_chain()
+			.map_err(|_| internal_err("fetch header failed"))?;
+
+		let from = request.from.unwrap_or_default();
+		let gas_price = request.gas_price.unwrap_or_default();
+		let gas_limit = request.gas.unwrap_or(U256::max_value());
+		let data = request.data.map(|d| d.0).unwrap_or_default();
+
+		let fee_payer = self.client.runtime_api()
+			.fee_payer(
+				&BlockId::Hash(header.hash()),
+				from,
+				request.to,
+				data,
+				gas_limit,
+				gas_price,
+			)
+			.map_err(|_| internal_err("fetch runtime fee payer failed"))?;
+
+		Ok(if fee_payer == from { None } else { Some(fee_payer) })
+	}
+
 	fn transaction_by_hash(&self, hash: H256) -> Result<Option<Transaction>> {
 		let header = self
 			.select_chain
@@ -603,6 +814,7 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 				},
 				gas_used: Some(receipt.used_gas),
 				contract_address: status.contract_address,
+				sponsor: status.sponsor,
 				logs: {
 					let mut pre_receipts_log_index = None;
 					if cumulative_receipts.len() > 0 {
@@ -664,8 +876,8 @@ impl<B, C, SC, P, CT, BE> EthApiT for EthApi<B, C, SC, P, CT, BE> where
 		unimplemented!("compile_serpent");
 	}
 
-	fn logs(&self, _: Filter) -> BoxFuture<Vec<Log>> {
-		unimplemented!("logs");
+	fn logs(&self, filter: Filter) -> BoxFuture<Vec<Log>> {
+		Box::new(future::result(self.filter_logs(filter)))
 	}
 
 	fn work(&self) -> Result<Work> {
@@ -18,9 +18,10 @@
 use sp_core::{U256, Pair, Public, H160, sr25519};
 use evm::{ConvertAccountId, HashTruncateConvertAccountId};
 use frontier_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, EVMConfig, GenesisConfig, GrandpaConfig, Signature,
-	SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, EVMAccessControlConfig, EVMConfig, EthereumConfig,
+	GenesisConfig, GrandpaConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
+use crate::ethereum_genesis::EthereumGenesis;
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -55,12 +56,12 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
-pub fn development_config() -> ChainSpec {
+pub fn development_config(ethereum_genesis: Option<EthereumGenesis>) -> ChainSpec {
 	ChainSpec::from_genesis(
 		"Development",
 		"dev",
 		ChainType::Development,
-		|| {
+		move || {
 			testnet_genesis(
 				vec![authority_keys_from_seed("Alice")],
 				get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -70,6 +71,7 @@ pub fn development_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				ethereum_genesis.clone(),
 				true,
 			)
 		},
@@ -81,12 +83,12 @@ pub fn development_config() -> ChainSpec {
 	)
 }
 
-pub fn local_testnet_config() -> ChainSpec {
+pub fn local_testnet_config(ethereum_genesis: Option<EthereumGenesis>) -> ChainSpec {
 	ChainSpec::from_genesis(
 		"Local Testnet",
 		"local_testnet",
 		ChainType::Local,
-		|| {
+		move || {
 			testnet_genesis(
 				vec![
 					authority_keys_from_seed("Alice"),
@@ -107,6 +109,7 @@ pub fn local_testnet_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				ethereum_genesis.clone(),
 				true,
 			)
 		},
@@ -118,38 +121,38 @@ pub fn local_testnet_config() -> ChainSpec {
 	)
 }
 
-fn testnet_genesis(
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
-	root_key: AccountId,
-	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool,
-) -> GenesisConfig {
+/// EVM accounts of new development chains: the EVM account of Alice and a built-in account,
+/// both pre-funded.
+fn development_evm_accounts() -> BTreeMap<H160, evm::GenesisAccount> {
 	let alice_account_id = get_account_id_from_seed::<sr25519::Public>("Alice");
 	let alice_evm_account_id =
 		HashTruncateConvertAccountId::<BlakeTwo256>::convert_account_id(&alice_account_id);
-	let mut evm_accounts = BTreeMap::new();
-	evm_accounts.insert(
-		alice_evm_account_id,
-		evm::GenesisAccount {
-			nonce: 0.into(),
-			balance: U256::MAX,
-			storage: BTreeMap::new(),
-			code: WASM_BINARY.to_vec(),
-		},
-	);
-
 	let built_in_evm_account =
 		H160::from_str("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b").unwrap();
-	let mut evm_accounts = BTreeMap::new();
-	evm_accounts.insert(
-		built_in_evm_account,
-		evm::GenesisAccount {
+
+	[alice_evm_account_id, built_in_evm_account].iter()
+		.map(|dev_account| (*dev_account, evm::GenesisAccount {
 			nonce: 0.into(),
 			balance: U256::MAX,
 			storage: BTreeMap::new(),
-			code: WASM_BINARY.to_vec(),
-		},
-	);
+			code: Vec::new(),
+		}))
+		.collect()
+}
+
+fn testnet_genesis(
+	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	root_key: AccountId,
+	endowed_accounts: Vec<AccountId>,
+	ethereum_genesis: Option<EthereumGenesis>,
+	_enable_println: bool,
+) -> GenesisConfig {
+	// An imported state is kept as it is, so that the chain commits the state root it was
+	// checked against.
+	let ethereum_genesis = ethereum_genesis.unwrap_or_else(|| EthereumGenesis {
+		accounts: development_evm_accounts(),
+		..Default::default()
+	});
 
 	GenesisConfig {
 		system: Some(SystemConfig {
@@ -174,7 +177,52 @@ fn testnet_genesis(
 		}),
 		sudo: Some(SudoConfig { key: root_key }),
 		evm: Some(EVMConfig {
-			accounts: evm_accounts,
+			accounts: ethereum_genesis.accounts,
+			frozen: ethereum_genesis.frozen,
 		}),
+		ethereum: Some(EthereumConfig {
+			chain_id: ethereum_genesis.chain_id,
+			block_gas_limit: ethereum_genesis.gas_limit,
+		}),
+		// Anyone may deploy and call contracts on development chains.
+		evm_access_control: Some(EVMAccessControlConfig {
+			paused: false,
+			deployers_restricted: false,
+			deployers: Vec::new(),
+		}),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn genesis(ethereum_genesis: Option<EthereumGenesis>) -> EVMConfig {
+		let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+		testnet_genesis(vec![authority_keys_from_seed("Alice")], alice, Vec::new(), ethereum_genesis, true)
+			.evm
+			.unwrap()
+	}
+
+	#[test]
+	fn test_imported_state_is_kept_as_it_is() {
+		let mut imported = EthereumGenesis::default();
+		let built_in_evm_account = H160::from_str("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b").unwrap();
+		imported.accounts.insert(built_in_evm_account, evm::GenesisAccount {
+			nonce: 1.into(),
+			balance: 7.into(),
+			storage: BTreeMap::new(),
+			code: Vec::new(),
+		});
+
+		let config = genesis(Some(imported.clone()));
+		assert_eq!(config.accounts, imported.accounts);
+		assert_eq!(evm::genesis_state_root(&config.accounts, &config.frozen), imported.state_root());
+	}
+
+	#[test]
+	fn test_new_state_has_development_accounts() {
+		let config = genesis(None);
+		assert_eq!(config.accounts, development_evm_accounts());
 	}
 }
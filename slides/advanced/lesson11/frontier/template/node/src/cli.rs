@@ -15,8 +15,53 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use sc_cli::{RunCmd, Subcommand};
+use std::path::PathBuf;
+use std::str::FromStr;
+use sc_cli::RunCmd;
 use structopt::StructOpt;
+use crate::evm_state::ExportEvmStateCmd;
+
+/// Block sealing strategy used instead of Aura and GRANDPA.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sealing {
+	/// Seal a block as soon as a transaction enters the pool.
+	Instant,
+	/// Seal blocks only when requested over RPC.
+	Manual,
+	/// Seal a block every given number of milliseconds.
+	Interval(u64),
+}
+
+impl FromStr for Sealing {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"instant" => Self::Instant,
+			"manual" => Self::Manual,
+			s => {
+				let millis = s.parse::<u64>()
+					.map_err(|_| "expected `instant`, `manual` or an interval in milliseconds")?;
+				Self::Interval(millis)
+			}
+		})
+	}
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Subcommand {
+	/// A set of base subcommands handled by `sc_cli`.
+	#[structopt(flatten)]
+	Base(sc_cli::Subcommand),
+
+	/// Export the accounts, code and storage of the EVM pallet as a snapshot.
+	#[structopt(name = "export-evm-state")]
+	ExportEvmState(ExportEvmStateCmd),
+
+	/// Benchmark the extrinsics of the runtime pallets, such as `evm` and `ethereum`.
+	#[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
+	Benchmark(frame_benchmarking_cli::BenchmarkCmd),
+}
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -25,4 +70,16 @@ pub struct Cli {
 
 	#[structopt(flatten)]
 	pub run: RunCmd,
+
+	/// Seal blocks with manual seal instead of Aura and GRANDPA, for development.
+	/// One of `instant`, `manual` or a block interval in milliseconds.
+	#[structopt(long = "sealing")]
+	pub sealing: Option<Sealing>,
+
+	/// Import the EVM state, chain id and gas limit of the `dev` and `local` chains
+	/// from a geth-compatible `genesis.json` file, or a `.scale` snapshot written by
+	/// `export-evm-state`. The imported state is used as it is, without the pre-funded
+	/// development accounts.
+	#[structopt(long = "ethereum-genesis", value_name = "PATH", parse(from_os_str))]
+	pub ethereum_genesis: Option<PathBuf>,
 }
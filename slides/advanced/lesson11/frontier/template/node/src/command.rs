@@ -16,9 +16,12 @@
 // limitations under the License.
 
 use crate::chain_spec;
-use crate::cli::Cli;
+use std::sync::Arc;
+use crate::cli::{Cli, Subcommand};
+use crate::ethereum_genesis::EthereumGenesis;
 use crate::service;
 use sc_cli::{SubstrateCli, RuntimeVersion, Role, ChainSpec};
+use frontier_template_runtime::{opaque::Block, RuntimeApi};
 
 impl SubstrateCli for Cli {
 	fn impl_name() -> String {
@@ -46,9 +49,13 @@ impl SubstrateCli for Cli {
 	}
 
 	fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+		let ethereum_genesis = self.ethereum_genesis.as_ref()
+			.map(|path| EthereumGenesis::from_file(path))
+			.transpose()?;
+
 		Ok(match id {
-			"dev" => Box::new(chain_spec::development_config()),
-			"" | "local" => Box::new(chain_spec::local_testnet_config()),
+			"dev" => Box::new(chain_spec::development_config(ethereum_genesis)),
+			"" | "local" => Box::new(chain_spec::local_testnet_config(ethereum_genesis)),
 			path => Box::new(chain_spec::ChainSpec::from_json_file(
 				std::path::PathBuf::from(path),
 			)?),
@@ -65,18 +72,36 @@ pub fn run() -> sc_cli::Result<()> {
 	let cli = Cli::from_args();
 
 	match &cli.subcommand {
-		Some(subcommand) => {
+		Some(Subcommand::Base(subcommand)) => {
 			let runner = cli.create_runner(subcommand)?;
 			runner.run_subcommand(subcommand, |config| {
-				let (builder, _, _) = new_full_start!(config);
+				let (builder, _, _, _) = new_full_start!(config, cli.sealing);
 				Ok(builder.to_chain_ops_parts())
 			})
 		}
+		Some(Subcommand::ExportEvmState(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let client = sc_service::new_full_client::<Block, RuntimeApi, service::Executor>(&config)?;
+				cmd.run(Arc::new(client))
+			})
+		}
+		Some(Subcommand::Benchmark(cmd)) => {
+			if cfg!(feature = "runtime-benchmarks") {
+				let runner = cli.create_runner(cmd)?;
+
+				runner.sync_run(|config| cmd.run::<Block, service::Executor>(config))
+			} else {
+				println!("Benchmarking wasn't enabled when building the node. \
+				You can enable it with `--features runtime-benchmarks`.");
+				Ok(())
+			}
+		}
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
 			runner.run_node_until_exit(|config| match config.role {
 				Role::Light => service::new_light(config),
-				_ => service::new_full(config),
+				_ => service::new_full(config, cli.sealing),
 			})
 		}
 	}
@@ -0,0 +1,201 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Development sealing helpers: the timestamp provider used by the manual-seal
+//! authorship task and the `evm_*` RPCs Ethereum test suites rely on.
+
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use parking_lot::Mutex;
+use futures::{
+	channel::{mpsc, oneshot},
+	FutureExt, SinkExt, TryFutureExt,
+};
+use jsonrpc_core::{BoxFuture, Result, ErrorCode, Error};
+use sp_core::{H256, U256};
+use sp_blockchain::HeaderBackend;
+use sc_client_api::backend::Backend;
+use sp_inherents::{InherentData, InherentIdentifier, ProvideInherentData};
+use sc_consensus_manual_seal::rpc::EngineCommand;
+use frontier_rpc_core::EvmApi;
+use frontier_template_runtime::{opaque::Block, Hash, SLOT_DURATION};
+
+fn internal_err(message: &str) -> Error {
+	Error {
+		code: ErrorCode::InternalError,
+		message: message.to_string(),
+		data: None
+	}
+}
+
+/// State shared between the development RPCs and the sealing task.
+#[derive(Default)]
+pub struct DevState {
+	/// Milliseconds added to the wall clock by `evm_increaseTime`.
+	time_offset: Mutex<u64>,
+	/// Timestamp of the last sealed block, in milliseconds.
+	last_timestamp: Mutex<u64>,
+	/// Block hashes recorded by `evm_snapshot`, indexed by snapshot id - 1.
+	snapshots: Mutex<Vec<Hash>>,
+}
+
+/// Timestamp inherent provider for development sealing.
+///
+/// Aura only accepts one block per slot, so every sealed block is moved at
+/// least one slot past its parent, on top of any `evm_increaseTime` offset.
+pub struct DevTimestamp {
+	state: Arc<DevState>,
+}
+
+impl DevTimestamp {
+	pub fn new(state: Arc<DevState>) -> Self {
+		Self { state }
+	}
+}
+
+impl ProvideInherentData for DevTimestamp {
+	fn inherent_identifier(&self) -> &'static InherentIdentifier {
+		&sp_timestamp::INHERENT_IDENTIFIER
+	}
+
+	fn provide_inherent_data(
+		&self,
+		inherent_data: &mut InherentData,
+	) -> std::result::Result<(), sp_inherents::Error> {
+		let now = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_err(|_| sp_inherents::Error::from("Current time is before unix epoch"))?
+			.as_millis() as u64;
+
+		let mut last = self.state.last_timestamp.lock();
+		let timestamp = std::cmp::max(
+			now + *self.state.time_offset.lock(),
+			*last + SLOT_DURATION,
+		);
+		*last = timestamp;
+
+		inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp)
+	}
+
+	fn error_to_string(&self, error: &[u8]) -> Option<String> {
+		sp_timestamp::InherentError::try_from(&sp_timestamp::INHERENT_IDENTIFIER, error)
+			.map(|e| format!("{:?}", e))
+	}
+}
+
+/// Implementation of the `evm_*` development RPCs on top of manual seal.
+pub struct EvmDev<C, BE> {
+	client: Arc<C>,
+	backend: Arc<BE>,
+	command_sink: mpsc::Sender<EngineCommand<Hash>>,
+	state: Arc<DevState>,
+}
+
+impl<C, BE> EvmDev<C, BE> {
+	pub fn new(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		command_sink: mpsc::Sender<EngineCommand<Hash>>,
+		state: Arc<DevState>,
+	) -> Self {
+		Self { client, backend, command_sink, state }
+	}
+
+	fn seal(&self) -> BoxFuture<H256> {
+		let mut sink = self.command_sink.clone();
+		let future = async move {
+			let (sender, receiver) = oneshot::channel();
+			sink.send(EngineCommand::SealNewBlock {
+				create_empty: true,
+				finalize: false,
+				parent_hash: None,
+				sender: Some(sender),
+			}).await.map_err(|_| internal_err("sealing task is not running"))?;
+
+			match receiver.await {
+				Ok(Ok(created)) => Ok(created.hash),
+				Ok(Err(e)) => Err(internal_err(&format!("{}", e))),
+				Err(_) => Err(internal_err("sealing task dropped the request")),
+			}
+		};
+
+		Box::new(future.boxed().compat())
+	}
+}
+
+impl<C, BE> EvmApi for EvmDev<C, BE> where
+	C: HeaderBackend<Block> + Send + Sync + 'static,
+	BE: Backend<Block> + 'static,
+{
+	fn mine(&self) -> BoxFuture<H256> {
+		self.seal()
+	}
+
+	fn snapshot(&self) -> Result<U256> {
+		let mut snapshots = self.state.snapshots.lock();
+		snapshots.push(self.client.info().best_hash);
+		Ok(U256::from(snapshots.len()))
+	}
+
+	fn revert(&self, id: U256) -> BoxFuture<bool> {
+		let mut snapshots = self.state.snapshots.lock();
+		if id.is_zero() || id > U256::from(snapshots.len()) {
+			return Box::new(jsonrpc_core::futures::future::ok(false));
+		}
+		let index = id.as_usize() - 1;
+		let hash = snapshots[index];
+
+		let info = self.client.info();
+		let number = match self.client.number(hash) {
+			Ok(Some(number)) => number,
+			Ok(None) => return Box::new(jsonrpc_core::futures::future::ok(false)),
+			Err(e) => return Box::new(jsonrpc_core::futures::future::err(
+				internal_err(&format!("{:?}", e))
+			)),
+		};
+		// A snapshot taken on a branch that was reverted away is gone.
+		if number > info.best_number || self.client.hash(number).ok().flatten() != Some(hash) {
+			return Box::new(jsonrpc_core::futures::future::ok(false));
+		}
+
+		// Blocks sealed in development mode are never finalized, so the
+		// best block can be moved back to the snapshot without sealing a
+		// new block, keeping the chain height where it was when the
+		// snapshot was taken.
+		let count = info.best_number - number;
+		match self.backend.revert(count, false) {
+			Ok(reverted) if reverted == count => (),
+			Ok(_) => return Box::new(jsonrpc_core::futures::future::ok(false)),
+			Err(e) => return Box::new(jsonrpc_core::futures::future::err(
+				internal_err(&format!("{:?}", e))
+			)),
+		}
+
+		// Reverting consumes the snapshot and every one taken after it;
+		// earlier snapshots are ancestors of the new best block and stay
+		// valid.
+		snapshots.truncate(index);
+
+		Box::new(jsonrpc_core::futures::future::ok(true))
+	}
+
+	fn increase_time(&self, seconds: u64) -> Result<u64> {
+		let mut offset = self.state.time_offset.lock();
+		*offset = offset.saturating_add(seconds.saturating_mul(1000));
+		Ok(*offset / 1000)
+	}
+}
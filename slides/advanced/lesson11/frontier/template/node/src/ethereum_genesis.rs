@@ -0,0 +1,360 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversion between the EVM and Ethereum genesis and portable snapshots: geth-compatible
+//! `genesis.json` files and SCALE encoded snapshots.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::str::FromStr;
+use codec::{Encode, Decode};
+use rustc_hex::{FromHex, ToHex};
+use serde::{Deserialize, Serialize};
+use sp_core::{H160, H256, U256};
+
+/// Chain id used when no Ethereum genesis is supplied.
+pub const DEFAULT_CHAIN_ID: u64 = 42;
+
+/// Ethereum part of the genesis state.
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct EthereumGenesis {
+	/// Chain id transactions are signed for.
+	pub chain_id: u64,
+	/// Gas limit reported in Ethereum block headers.
+	pub gas_limit: U256,
+	/// Pre-allocated EVM accounts.
+	pub accounts: BTreeMap<H160, evm::GenesisAccount>,
+	/// Contracts whose code is frozen, as set by `pallet_evm::Module::freeze_contract`.
+	pub frozen: BTreeSet<H160>,
+}
+
+impl Default for EthereumGenesis {
+	fn default() -> Self {
+		Self {
+			chain_id: DEFAULT_CHAIN_ID,
+			gas_limit: U256::from(u32::max_value()),
+			accounts: BTreeMap::new(),
+			frozen: BTreeSet::new(),
+		}
+	}
+}
+
+/// SCALE encoded snapshot, as written by `export-evm-state --format scale`.
+#[derive(Encode, Decode)]
+struct EvmStateSnapshot {
+	state_root: H256,
+	genesis: EthereumGenesis,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GethGenesis {
+	config: GethConfig,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	gas_limit: Option<String>,
+	#[serde(default)]
+	alloc: BTreeMap<String, GethAccount>,
+	/// Not part of the geth format: the `state_root` of an exported snapshot.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	evm_state_root: Option<H256>,
+	/// Not part of the geth format: the frozen contracts of an exported snapshot.
+	#[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+	evm_frozen: BTreeSet<H160>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GethConfig {
+	chain_id: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct GethAccount {
+	balance: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	nonce: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	code: Option<String>,
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	storage: BTreeMap<String, String>,
+}
+
+fn strip_hex_prefix(s: &str) -> Option<&str> {
+	if s.starts_with("0x") || s.starts_with("0X") {
+		Some(&s[2..])
+	} else {
+		None
+	}
+}
+
+/// Parses a quantity the way geth does: `0x`-prefixed hex, otherwise decimal.
+fn parse_quantity(s: &str) -> Result<U256, String> {
+	match strip_hex_prefix(s) {
+		Some("") => Ok(U256::zero()),
+		Some(hex) if hex.len() > 64 => Err(format!("Invalid quantity {}: more than 256 bits", s)),
+		Some(hex) => U256::from_str(hex).map_err(|e| format!("Invalid quantity {}: {:?}", s, e)),
+		None => U256::from_dec_str(s).map_err(|e| format!("Invalid quantity {}: {:?}", s, e)),
+	}
+}
+
+fn parse_bytes(s: &str) -> Result<Vec<u8>, String> {
+	strip_hex_prefix(s).unwrap_or(s)
+		.from_hex()
+		.map_err(|e| format!("Invalid hex data {}: {}", s, e))
+}
+
+/// Parses a storage slot or value, left-padding short values to 32 bytes.
+fn parse_word(s: &str) -> Result<H256, String> {
+	let mut word = [0u8; 32];
+	parse_quantity(&format!("0x{}", strip_hex_prefix(s).unwrap_or(s)))?
+		.to_big_endian(&mut word);
+	Ok(H256::from(word))
+}
+
+fn check_state_root(genesis: EthereumGenesis, expected: Option<H256>) -> Result<EthereumGenesis, String> {
+	match expected {
+		Some(expected) if expected != genesis.state_root() => Err(format!(
+			"EVM state root mismatch: source chain has {:?}, imported state has {:?}",
+			expected,
+			genesis.state_root(),
+		)),
+		_ => Ok(genesis),
+	}
+}
+
+/// Rejects parameters that would make every Ethereum transaction invalid.
+fn check_params(genesis: EthereumGenesis) -> Result<EthereumGenesis, String> {
+	if genesis.chain_id == 0 {
+		return Err("Ethereum genesis chain id must not be zero".to_string());
+	}
+	if genesis.gas_limit.is_zero() {
+		return Err("Ethereum genesis gas limit must not be zero".to_string());
+	}
+	Ok(genesis)
+}
+
+impl EthereumGenesis {
+	/// Ethereum state root of the accounts, as `pallet_evm` commits them at genesis. Snapshots
+	/// carry the root of the chain they were exported from, so that importing them checks the
+	/// new genesis against it.
+	pub fn state_root(&self) -> H256 {
+		evm::genesis_state_root(&self.accounts, &self.frozen)
+	}
+
+	/// Loads a geth `genesis.json` file, or a SCALE snapshot if the file has a `scale`
+	/// extension. Snapshots carrying a state root are checked against it.
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let bytes = std::fs::read(path)
+			.map_err(|e| format!("Error opening Ethereum genesis {}: {}", path.display(), e))?;
+		match path.extension() {
+			Some(extension) if extension == "scale" => Self::from_scale_bytes(&bytes),
+			_ => Self::from_json_bytes(&bytes),
+		}
+	}
+
+	/// Decodes a SCALE snapshot.
+	pub fn from_scale_bytes(mut bytes: &[u8]) -> Result<Self, String> {
+		let snapshot = EvmStateSnapshot::decode(&mut bytes)
+			.map_err(|e| format!("Error decoding EVM state snapshot: {}", e.what()))?;
+		check_state_root(check_params(snapshot.genesis)?, Some(snapshot.state_root))
+	}
+
+	/// Encodes the state as a SCALE snapshot.
+	pub fn to_scale_bytes(&self) -> Vec<u8> {
+		EvmStateSnapshot {
+			state_root: self.state_root(),
+			genesis: self.clone(),
+		}.encode()
+	}
+
+	/// Renders the state as a geth `genesis.json`, including the state root.
+	pub fn to_json_string(&self) -> Result<String, String> {
+		let alloc = self.accounts.iter()
+			.map(|(address, account)| {
+				let geth_account = GethAccount {
+					balance: format!("{:#x}", account.balance),
+					nonce: Some(format!("{:#x}", account.nonce)),
+					code: if account.code.is_empty() {
+						None
+					} else {
+						Some(format!("0x{}", account.code.to_hex::<String>()))
+					},
+					storage: account.storage.iter()
+						.map(|(index, value)| (format!("{:?}", index), format!("{:?}", value)))
+						.collect(),
+				};
+				(format!("{:?}", address), geth_account)
+			})
+			.collect();
+
+		serde_json::to_string_pretty(&GethGenesis {
+			config: GethConfig { chain_id: self.chain_id },
+			gas_limit: Some(format!("{:#x}", self.gas_limit)),
+			alloc,
+			evm_state_root: Some(self.state_root()),
+			evm_frozen: self.frozen.clone(),
+		}).map_err(|e| format!("Error serializing Ethereum genesis: {}", e))
+	}
+
+	/// Parses the contents of a geth `genesis.json` file.
+	pub fn from_json_bytes(bytes: &[u8]) -> Result<Self, String> {
+		let genesis: GethGenesis = serde_json::from_slice(bytes)
+			.map_err(|e| format!("Error parsing Ethereum genesis: {}", e))?;
+
+		let mut accounts = BTreeMap::new();
+		for (address, account) in genesis.alloc {
+			let address = H160::from_str(strip_hex_prefix(&address).unwrap_or(&address))
+				.map_err(|e| format!("Invalid address {}: {:?}", address, e))?;
+
+			let mut storage = BTreeMap::new();
+			for (index, value) in &account.storage {
+				storage.insert(parse_word(index)?, parse_word(value)?);
+			}
+
+			accounts.insert(address, evm::GenesisAccount {
+				nonce: account.nonce.as_deref().map(parse_quantity).transpose()?.unwrap_or_default(),
+				balance: parse_quantity(&account.balance)?,
+				storage,
+				code: account.code.as_deref().map(parse_bytes).transpose()?.unwrap_or_default(),
+			});
+		}
+
+		check_state_root(check_params(Self {
+			chain_id: genesis.config.chain_id,
+			gas_limit: match genesis.gas_limit {
+				Some(gas_limit) => parse_quantity(&gas_limit)?,
+				None => Self::default().gas_limit,
+			},
+			accounts,
+			frozen: genesis.evm_frozen,
+		})?, genesis.evm_state_root)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn word(value: u64) -> H256 {
+		H256::from_low_u64_be(value)
+	}
+
+	#[test]
+	fn test_parse_quantity() {
+		assert_eq!(parse_quantity("0x10"), Ok(U256::from(16)));
+		assert_eq!(parse_quantity("0X1f"), Ok(U256::from(31)));
+		assert_eq!(parse_quantity("0x"), Ok(U256::zero()));
+		assert_eq!(parse_quantity("1000"), Ok(U256::from(1000)));
+		assert_eq!(
+			parse_quantity("115792089237316195423570985008687907853269984665640564039457584007913129639935"),
+			Ok(U256::max_value()),
+		);
+		assert!(parse_quantity("0xzz").is_err());
+		assert!(parse_quantity("ten").is_err());
+		assert!(parse_quantity(&format!("0x1{}", "0".repeat(64))).is_err());
+	}
+
+	#[test]
+	fn test_parse_word() {
+		assert_eq!(parse_word("0x01"), Ok(word(1)));
+		assert_eq!(parse_word("02"), Ok(word(2)));
+		assert_eq!(parse_word(&format!("0x{}", "ff".repeat(32))), Ok(H256::repeat_byte(0xff)));
+	}
+
+	#[test]
+	fn test_from_json_bytes() {
+		let genesis = EthereumGenesis::from_json_bytes(br#"{
+			"config": { "chainId": 1337, "homesteadBlock": 0 },
+			"gasLimit": "0x1c9c380",
+			"difficulty": "0x1",
+			"alloc": {
+				"0x0000000000000000000000000000000000000001": {
+					"balance": "1000000000000000000"
+				},
+				"0000000000000000000000000000000000000002": {
+					"balance": "0x10",
+					"nonce": "0x2",
+					"code": "0x6000",
+					"storage": {
+						"0x01": "0x02",
+						"0x0000000000000000000000000000000000000000000000000000000000000003": "4"
+					}
+				}
+			}
+		}"#).unwrap();
+
+		assert_eq!(genesis.chain_id, 1337);
+		assert_eq!(genesis.gas_limit, U256::from(30_000_000));
+		assert_eq!(genesis.accounts.len(), 2);
+
+		let first = &genesis.accounts[&H160::from_low_u64_be(1)];
+		assert_eq!(first.balance, U256::from(1_000_000_000_000_000_000u64));
+		assert_eq!(first.nonce, U256::zero());
+		assert!(first.code.is_empty());
+		assert!(first.storage.is_empty());
+
+		let second = &genesis.accounts[&H160::from_low_u64_be(2)];
+		assert_eq!(second.balance, U256::from(16));
+		assert_eq!(second.nonce, U256::from(2));
+		assert_eq!(second.code, vec![0x60, 0x00]);
+		assert_eq!(second.storage.get(&word(1)), Some(&word(2)));
+		assert_eq!(second.storage.get(&word(3)), Some(&word(4)));
+	}
+
+	#[test]
+	fn test_from_json_bytes_without_gas_limit() {
+		let genesis = EthereumGenesis::from_json_bytes(br#"{ "config": { "chainId": 5 } }"#).unwrap();
+
+		assert_eq!(genesis.chain_id, 5);
+		assert_eq!(genesis.gas_limit, EthereumGenesis::default().gas_limit);
+		assert!(genesis.accounts.is_empty());
+	}
+
+	#[test]
+	fn test_from_json_bytes_rejects_invalid_chain_parameters() {
+		assert!(EthereumGenesis::from_json_bytes(br#"{ "config": {} }"#).is_err());
+		assert!(EthereumGenesis::from_json_bytes(br#"{ "config": { "chainId": 0 } }"#).is_err());
+		assert!(EthereumGenesis::from_json_bytes(
+			br#"{ "config": { "chainId": 1 }, "gasLimit": "0x0" }"#
+		).is_err());
+	}
+
+	#[test]
+	fn test_from_json_bytes_rejects_invalid_accounts() {
+		assert!(EthereumGenesis::from_json_bytes(
+			br#"{ "config": { "chainId": 1 }, "alloc": { "0x01": { "balance": "1" } } }"#
+		).is_err());
+		assert!(EthereumGenesis::from_json_bytes(br#"{
+			"config": { "chainId": 1 },
+			"alloc": { "0x0000000000000000000000000000000000000001": { "balance": "0xzz" } }
+		}"#).is_err());
+	}
+
+	#[test]
+	fn test_json_round_trip() {
+		let mut genesis = EthereumGenesis::default();
+		genesis.accounts.insert(H160::repeat_byte(0x11), evm::GenesisAccount {
+			nonce: U256::from(1),
+			balance: U256::from(1_000_000),
+			storage: vec![(word(1), word(0xff))].into_iter().collect(),
+			code: vec![0x60, 0x00, 0x60, 0x00, 0xf3],
+		});
+
+		let json = genesis.to_json_string().unwrap();
+		assert_eq!(EthereumGenesis::from_json_bytes(json.as_bytes()), Ok(genesis));
+	}
+}
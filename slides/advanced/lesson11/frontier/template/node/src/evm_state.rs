@@ -0,0 +1,384 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `export-evm-state` command.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use codec::Decode;
+use log::info;
+use sc_cli::{BlockNumberOrHash, CliConfiguration, PruningParams, SharedParams};
+use sc_client_api::{backend::{Backend, StateBackend}, StorageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, storage::StorageKey, H160, H256, U256};
+use sp_runtime::{generic::BlockId, traits::BlakeTwo256};
+use structopt::StructOpt;
+use frontier_template_runtime::{opaque::Block, EVMAccount};
+use crate::ethereum_genesis::EthereumGenesis;
+
+/// Output format of an EVM state snapshot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SnapshotFormat {
+	/// Geth compatible `genesis.json`.
+	Json,
+	/// SCALE encoded snapshot.
+	Scale,
+}
+
+impl FromStr for SnapshotFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"json" => Ok(Self::Json),
+			"scale" => Ok(Self::Scale),
+			_ => Err("expected `json` or `scale`".into()),
+		}
+	}
+}
+
+/// The `export-evm-state` command used to export the accounts, code and storage of
+/// `pallet_evm` at a given block.
+#[derive(Debug, StructOpt)]
+pub struct ExportEvmStateCmd {
+	/// Block hash or number to export the state of. Defaults to the best block.
+	#[structopt(long = "at", value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Snapshot format, `json` or `scale`.
+	#[structopt(long = "format", value_name = "FORMAT", default_value = "json")]
+	pub format: SnapshotFormat,
+
+	/// Output file. Defaults to stdout.
+	#[structopt(long = "output", short = "o", parse(from_os_str))]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+/// Length of the keys of `AccountStorages` after its prefix, when keyed by account only, as
+/// before the storage generations of accounts.
+const V1_STORAGE_KEY_LEN: usize = 16 + 20 + 16 + 32;
+
+fn storage_prefix(module: &[u8], item: &[u8]) -> Vec<u8> {
+	let mut prefix = twox_128(module).to_vec();
+	prefix.extend_from_slice(&twox_128(item));
+	prefix
+}
+
+/// Strips a `blake2_128_concat` hashed key, returning the key and the remaining bytes.
+fn blake2_128_concat_key<K: Decode>(bytes: &[u8]) -> Result<(K, &[u8]), String> {
+	if bytes.len() < 16 {
+		return Err("Storage key too short".into());
+	}
+	let mut input = &bytes[16..];
+	let key = K::decode(&mut input).map_err(|e| format!("Invalid storage key: {}", e.what()))?;
+	Ok((key, input))
+}
+
+fn empty_account() -> evm::GenesisAccount {
+	evm::GenesisAccount {
+		nonce: U256::zero(),
+		balance: U256::zero(),
+		storage: Default::default(),
+		code: Vec::new(),
+	}
+}
+
+fn decode_value<V: Decode>(bytes: &[u8]) -> Result<V, String> {
+	V::decode(&mut &bytes[..]).map_err(|e| format!("Invalid storage value: {}", e.what()))
+}
+
+/// Reads the EVM state and the Ethereum state root committed by `pallet_evm`, given the
+/// storage pairs under the prefix of a module and storage item, with the prefix stripped
+/// from the keys.
+pub fn read_evm_state<F>(storage: F) -> Result<(EthereumGenesis, H256), String> where
+	F: Fn(&[u8], &[u8]) -> Result<Vec<(Vec<u8>, Vec<u8>)>, String>,
+{
+	let mut genesis = EthereumGenesis::default();
+
+	for (key, value) in storage(b"EVM", b"Accounts")? {
+		let (address, _) = blake2_128_concat_key::<H160>(&key)?;
+		let account: EVMAccount = decode_value(&value)?;
+		let genesis_account = genesis.accounts.entry(address).or_insert_with(empty_account);
+		genesis_account.nonce = account.nonce;
+		genesis_account.balance = account.balance;
+	}
+	for (key, value) in storage(b"EVM", b"AccountCodes")? {
+		let (address, _) = blake2_128_concat_key::<H160>(&key)?;
+		genesis.accounts.entry(address).or_insert_with(empty_account).code = decode_value(&value)?;
+	}
+	// Frozen contracts are exported with their original code, and frozen again on import.
+	for (key, value) in storage(b"EVM", b"FrozenCodes")? {
+		let (address, _) = blake2_128_concat_key::<H160>(&key)?;
+		genesis.accounts.entry(address).or_insert_with(empty_account).code = decode_value(&value)?;
+		genesis.frozen.insert(address);
+	}
+	// Slots of previous storage generations belong to deleted storage not removed yet.
+	let mut generations = BTreeMap::new();
+	for (key, value) in storage(b"EVM", b"AccountStorageGenerations")? {
+		let (address, _) = blake2_128_concat_key::<H160>(&key)?;
+		generations.insert(address, decode_value::<u32>(&value)?);
+	}
+	for (key, value) in storage(b"EVM", b"AccountStorages")? {
+		// Slots written by account only belong to the first generation until they are migrated.
+		let (address, generation, rest) = if key.len() == V1_STORAGE_KEY_LEN {
+			let (address, rest) = blake2_128_concat_key::<H160>(&key)?;
+			(address, 0, rest)
+		} else {
+			let ((address, generation), rest) = blake2_128_concat_key::<(H160, u32)>(&key)?;
+			(address, generation, rest)
+		};
+		if generation != generations.get(&address).copied().unwrap_or(0) {
+			continue;
+		}
+		let (index, _) = blake2_128_concat_key::<H256>(rest)?;
+		genesis.accounts.entry(address).or_insert_with(empty_account)
+			.storage.insert(index, decode_value(&value)?);
+	}
+
+	let value = |module: &[u8], item: &[u8]| -> Result<Option<Vec<u8>>, String> {
+		Ok(storage(module, item)?.into_iter()
+			.find(|(key, _)| key.is_empty())
+			.map(|(_, value)| value))
+	};
+	// Both are set at genesis, so missing values mean `pallet_ethereum` storage moved.
+	genesis.chain_id = value(b"Example", b"ChainId")?
+		.ok_or_else(|| "No Ethereum chain id in storage".to_string())
+		.and_then(|chain_id| decode_value::<u64>(&chain_id))?;
+	genesis.gas_limit = value(b"Example", b"BlockGasLimit")?
+		.ok_or_else(|| "No Ethereum block gas limit in storage".to_string())
+		.and_then(|gas_limit| decode_value::<U256>(&gas_limit))?;
+	let state_root = value(b"EVM", b"StateRoot")?
+		.ok_or_else(|| "No EVM state root in storage".to_string())
+		.and_then(|state_root| decode_value::<H256>(&state_root))?;
+
+	Ok((genesis, state_root))
+}
+
+impl ExportEvmStateCmd {
+	/// Run the `export-evm-state` command.
+	pub fn run<C, BE>(&self, client: Arc<C>) -> sc_cli::Result<()> where
+		BE: Backend<Block>,
+		BE::State: StateBackend<BlakeTwo256>,
+		C: StorageProvider<Block, BE> + HeaderBackend<Block>,
+	{
+		let at = match &self.at {
+			Some(at) => at.parse::<Block>()?,
+			None => BlockId::Hash(client.info().best_hash),
+		};
+
+		info!("Exporting EVM state at {}...", at);
+		let (genesis, state_root) = read_evm_state(|module, item| {
+			let prefix = storage_prefix(module, item);
+			client.storage_pairs(&at, &StorageKey(prefix.clone()))
+				.map(|pairs| pairs.into_iter()
+					.map(|(key, value)| (key.0[prefix.len()..].to_vec(), value.0))
+					.collect::<Vec<_>>())
+				.map_err(|e| format!("Error reading state at {}: {:?}", at, e))
+		})?;
+
+		// The snapshot carries the state root of the accounts, which importing it checks the
+		// new genesis against. It must be the root the chain committed at this block.
+		if genesis.state_root() != state_root {
+			return Err(format!(
+				"Exported EVM state has root {:?}, but the state root at {} is {:?}",
+				genesis.state_root(),
+				at,
+				state_root,
+			).into());
+		}
+
+		info!(
+			"Exported {} EVM accounts, state root {:?}",
+			genesis.accounts.len(),
+			state_root,
+		);
+
+		let bytes = match self.format {
+			SnapshotFormat::Json => genesis.to_json_string()?.into_bytes(),
+			SnapshotFormat::Scale => genesis.to_scale_bytes(),
+		};
+		match &self.output {
+			Some(path) => std::fs::write(path, bytes)?,
+			None => std::io::stdout().write_all(&bytes)?,
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportEvmStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use sp_core::{hashing::blake2_128, storage::Storage};
+	use frontier_template_runtime::{Runtime, EVMConfig, EthereumConfig};
+
+	fn genesis() -> EthereumGenesis {
+		let mut genesis = EthereumGenesis::default();
+		genesis.chain_id = 1337;
+		genesis.accounts.insert(H160::repeat_byte(0x01), evm::GenesisAccount {
+			nonce: U256::zero(),
+			balance: U256::from(1_000_000),
+			storage: Default::default(),
+			code: Vec::new(),
+		});
+		genesis.accounts.insert(H160::repeat_byte(0x02), evm::GenesisAccount {
+			nonce: U256::one(),
+			balance: U256::zero(),
+			storage: vec![
+				(H256::from_low_u64_be(1), H256::from_low_u64_be(0xff)),
+				(H256::from_low_u64_be(2), H256::from_low_u64_be(0xee)),
+			].into_iter().collect(),
+			code: vec![0x60, 0x00, 0x54, 0x00],
+		});
+		genesis.accounts.insert(H160::repeat_byte(0x03), evm::GenesisAccount {
+			nonce: U256::one(),
+			balance: U256::from(7),
+			storage: vec![(H256::from_low_u64_be(3), H256::from_low_u64_be(4))].into_iter().collect(),
+			code: vec![0x60, 0x01, 0x00],
+		});
+		genesis.frozen.insert(H160::repeat_byte(0x03));
+		genesis
+	}
+
+	fn blake2_128_concat(bytes: &[u8]) -> Vec<u8> {
+		let mut key = blake2_128(bytes).to_vec();
+		key.extend_from_slice(bytes);
+		key
+	}
+
+	/// Builds the genesis into runtime storage and reads it back as `export-evm-state` does.
+	fn export(genesis: &EthereumGenesis) -> (EthereumGenesis, H256) {
+		export_with(genesis, |_| ())
+	}
+
+	/// As `export`, changing the runtime storage before reading it back.
+	fn export_with(
+		genesis: &EthereumGenesis,
+		change: impl FnOnce(&mut Storage),
+	) -> (EthereumGenesis, H256) {
+		let mut storage = EVMConfig {
+			accounts: genesis.accounts.clone(),
+			frozen: genesis.frozen.clone(),
+		}.build_storage::<Runtime>().unwrap();
+		EthereumConfig {
+			chain_id: genesis.chain_id,
+			block_gas_limit: genesis.gas_limit,
+		}.assimilate_storage::<Runtime>(&mut storage).unwrap();
+		change(&mut storage);
+
+		read_back(&storage).unwrap()
+	}
+
+	/// Reads runtime storage as `export-evm-state` does.
+	fn read_back(storage: &Storage) -> Result<(EthereumGenesis, H256), String> {
+		read_evm_state(|module, item| {
+			let prefix = storage_prefix(module, item);
+			Ok(storage.top.iter()
+				.filter(|(key, _)| key.starts_with(&prefix))
+				.map(|(key, value)| (key[prefix.len()..].to_vec(), value.clone()))
+				.collect())
+		})
+	}
+
+	#[test]
+	fn test_export_reads_back_genesis() {
+		let genesis = genesis();
+		let (exported, state_root) = export(&genesis);
+
+		assert_eq!(exported, genesis);
+		assert_eq!(exported.state_root(), state_root);
+	}
+
+	#[test]
+	fn test_export_reads_slots_not_migrated_yet() {
+		let genesis = genesis();
+		let address = H160::repeat_byte(0x02);
+		let index = H256::from_low_u64_be(1);
+
+		let (exported, _) = export_with(&genesis, |storage| {
+			let prefix = storage_prefix(b"EVM", b"AccountStorages");
+			let mut new_key = prefix.clone();
+			new_key.extend(blake2_128_concat(&(address, 0u32).encode()));
+			new_key.extend(blake2_128_concat(&index.encode()));
+			let value = storage.top.remove(&new_key).unwrap();
+
+			let mut old_key = prefix;
+			old_key.extend(blake2_128_concat(&address.encode()));
+			old_key.extend(blake2_128_concat(&index.encode()));
+			storage.top.insert(old_key, value);
+		});
+
+		assert_eq!(exported, genesis);
+	}
+
+	#[test]
+	fn test_export_fails_without_ethereum_parameters() {
+		let genesis = genesis();
+		let storage = EVMConfig {
+			accounts: genesis.accounts.clone(),
+			frozen: genesis.frozen.clone(),
+		}.build_storage::<Runtime>().unwrap();
+
+		assert_eq!(read_back(&storage).err(), Some("No Ethereum chain id in storage".to_string()));
+	}
+
+	#[test]
+	fn test_snapshot_round_trip() {
+		let (exported, state_root) = export(&genesis());
+
+		let imported = EthereumGenesis::from_scale_bytes(&exported.to_scale_bytes()).unwrap();
+		assert_eq!(imported, exported);
+		let imported = EthereumGenesis::from_json_bytes(exported.to_json_string().unwrap().as_bytes())
+			.unwrap();
+		assert_eq!(imported, exported);
+
+		// Importing into a new chain commits the same Ethereum state root.
+		assert_eq!(export(&imported).1, state_root);
+	}
+
+	#[test]
+	fn test_snapshot_with_other_state_is_rejected() {
+		let (exported, _) = export(&genesis());
+		let mut json: serde_json::Value = serde_json::from_str(&exported.to_json_string().unwrap())
+			.unwrap();
+		json["alloc"][format!("{:?}", H160::repeat_byte(0x01))]["balance"] = "0x1".into();
+
+		assert!(EthereumGenesis::from_json_bytes(json.to_string().as_bytes()).is_err());
+	}
+}
@@ -23,6 +23,9 @@ mod chain_spec;
 mod service;
 mod cli;
 mod command;
+mod dev;
+mod ethereum_genesis;
+mod evm_state;
 mod rpc;
 
 fn main() -> sc_cli::Result<()> {
@@ -28,6 +28,9 @@ use sc_rpc_api::DenyUnsafe;
 use sc_client_api::backend::{StorageProvider, Backend, StateBackend};
 use sp_runtime::traits::BlakeTwo256;
 use sp_block_builder::BlockBuilder;
+use sc_consensus_manual_seal::rpc::EngineCommand;
+use futures::channel::mpsc;
+use crate::dev::DevState;
 
 /// Light client extra dependencies.
 pub struct LightDeps<C, F, P> {
@@ -42,9 +45,11 @@ pub struct LightDeps<C, F, P> {
 }
 
 /// Full client dependencies.
-pub struct FullDeps<C, P, SC> {
+pub struct FullDeps<C, P, SC, BE> {
 	/// The client instance to use.
 	pub client: Arc<C>,
+	/// The backend instance to use.
+	pub backend: Arc<BE>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
 	/// The SelectChain Strategy
@@ -53,11 +58,15 @@ pub struct FullDeps<C, P, SC> {
 	pub deny_unsafe: DenyUnsafe,
 	/// The Node authority flag
 	pub is_authority: bool,
+	/// Manual seal command sink, present when running with `--sealing`
+	pub command_sink: Option<mpsc::Sender<EngineCommand<Hash>>>,
+	/// State shared with the development sealing task
+	pub dev_state: Arc<DevState>,
 }
 
 /// Instantiate all Full RPC extensions.
 pub fn create_full<C, P, M, SC, BE>(
-	deps: FullDeps<C, P, SC>,
+	deps: FullDeps<C, P, SC, BE>,
 ) -> jsonrpc_core::IoHandler<M> where
 	BE: Backend<Block> + 'static,
 	BE::State: StateBackend<BlakeTwo256>,
@@ -76,14 +85,20 @@ pub fn create_full<C, P, M, SC, BE>(
 	use substrate_frame_rpc_system::{FullSystem, SystemApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use frontier_rpc::{EthApi, EthApiServer};
+	use frontier_rpc_core::EvmApiServer;
+	use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApi};
+	use crate::dev::EvmDev;
 
 	let mut io = jsonrpc_core::IoHandler::default();
 	let FullDeps {
 		client,
+		backend,
 		pool,
 		select_chain,
 		deny_unsafe,
-		is_authority
+		is_authority,
+		command_sink,
+		dev_state,
 	} = deps;
 
 	io.extend_with(
@@ -102,6 +117,15 @@ pub fn create_full<C, P, M, SC, BE>(
 		))
 	);
 
+	if let Some(command_sink) = command_sink {
+		io.extend_with(
+			ManualSealApi::to_delegate(ManualSeal::new(command_sink.clone()))
+		);
+		io.extend_with(
+			EvmApiServer::to_delegate(EvmDev::new(client, backend, command_sink, dev_state))
+		);
+	}
+
 	io
 }
 
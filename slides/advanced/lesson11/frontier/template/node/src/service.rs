@@ -17,15 +17,19 @@
 
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
+use futures::{Stream, StreamExt, stream};
 use sc_client_api::ExecutorProvider;
 use sc_consensus::LongestChain;
-use frontier_template_runtime::{self, opaque::Block, RuntimeApi};
+use sc_consensus_manual_seal::rpc::EngineCommand;
+use frontier_template_runtime::{self, opaque::Block, Hash, RuntimeApi};
 use sc_service::{
 	error::{Error as ServiceError}, Configuration, ServiceBuilder, ServiceComponents,
 	TaskManager,
 };
+use crate::cli::Sealing;
 use sp_inherents::InherentDataProviders;
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
@@ -39,6 +43,7 @@ native_executor_instance!(
 	pub Executor,
 	frontier_template_runtime::api::dispatch,
 	frontier_template_runtime::native_version,
+	frame_benchmarking::benchmarking::HostFunctions,
 );
 
 /// Starts a `ServiceBuilder` for a full service.
@@ -46,12 +51,22 @@ native_executor_instance!(
 /// Use this macro if you don't actually need the full service, but just the builder in order to
 /// be able to perform chain operations.
 macro_rules! new_full_start {
-	($config:expr) => {{
+	($config:expr, $sealing:expr) => {{
 		use std::sync::Arc;
 		use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 
+		let sealing: Option<crate::cli::Sealing> = $sealing;
 		let mut import_setup = None;
 		let inherent_data_providers = sp_inherents::InherentDataProviders::new();
+		let dev_state = Arc::new(crate::dev::DevState::default());
+		let (command_sink, commands_stream) = futures::channel::mpsc::channel(1000);
+
+		if sealing.is_some() {
+			inherent_data_providers
+				.register_provider(crate::dev::DevTimestamp::new(dev_state.clone()))
+				.map_err(Into::into)
+				.map_err(sp_consensus::Error::InherentData)?;
+		}
 
 		let builder = sc_service::ServiceBuilder::new_full::<
 			frontier_template_runtime::opaque::Block, frontier_template_runtime::RuntimeApi, crate::service::Executor
@@ -80,6 +95,14 @@ macro_rules! new_full_start {
 				spawn_task_handle,
 				registry,
 			| {
+				if sealing.is_some() {
+					return Ok(sc_consensus_manual_seal::import_queue(
+						Box::new(client),
+						spawn_task_handle,
+						registry,
+					));
+				}
+
 				let select_chain = select_chain.take()
 					.ok_or_else(|| sc_service::Error::SelectChainRequired)?;
 
@@ -110,6 +133,7 @@ macro_rules! new_full_start {
 			})?
 			.with_rpc_extensions_builder(|builder| {
 				let client = builder.client().clone();
+				let backend = builder.backend().clone();
 				let is_authority: bool = builder.config().role.is_authority();
 				let pool = builder.pool().clone();
 				let select_chain = builder.select_chain().cloned()
@@ -118,32 +142,32 @@ macro_rules! new_full_start {
 				Ok(move |deny_unsafe| {
 					let deps = crate::rpc::FullDeps {
 						client: client.clone(),
+						backend: backend.clone(),
 						pool: pool.clone(),
 						select_chain: select_chain.clone(),
 						deny_unsafe,
-						is_authority
+						is_authority,
+						command_sink: sealing.map(|_| command_sink.clone()),
+						dev_state: dev_state.clone(),
 					};
 
 					crate::rpc::create_full(deps)
 				})
 			})?;
 
-		(builder, import_setup, inherent_data_providers)
+		(builder, import_setup, inherent_data_providers, commands_stream)
 	}}
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(config: Configuration, sealing: Option<Sealing>) -> Result<TaskManager, ServiceError> {
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
 	let name = config.network.node_name.clone();
 	let disable_grandpa = config.disable_grandpa;
 
-	let (builder, mut import_setup, inherent_data_providers) = new_full_start!(config);
-
-	let (block_import, grandpa_link) =
-		import_setup.take()
-			.expect("Link Half and Block Import are present for Full Services or setup failed before. qed");
+	let (builder, mut import_setup, inherent_data_providers, commands_stream) =
+		new_full_start!(config, sealing);
 
 	let ServiceComponents {
 		client, transaction_pool, task_manager, keystore, network, select_chain,
@@ -156,6 +180,64 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
 		})?
 		.build_full()?;
 
+	if let Some(sealing) = sealing {
+		let env = sc_basic_authorship::ProposerFactory::new(
+			client.clone(),
+			transaction_pool.clone(),
+			prometheus_registry.as_ref(),
+		);
+
+		let select_chain = select_chain
+			.ok_or(ServiceError::SelectChainRequired)?;
+
+		// RPC commands are always accepted, whatever triggers sealing otherwise.
+		let commands_stream: Pin<Box<dyn Stream<Item = EngineCommand<Hash>> + Send>> = match sealing {
+			Sealing::Manual => Box::pin(commands_stream),
+			Sealing::Instant => Box::pin(stream::select(
+				commands_stream,
+				transaction_pool.pool().validated_pool().import_notification_stream()
+					.map(|_| EngineCommand::SealNewBlock {
+						create_empty: false,
+						finalize: false,
+						parent_hash: None,
+						sender: None,
+					}),
+			)),
+			Sealing::Interval(millis) => Box::pin(stream::select(
+				commands_stream,
+				stream::unfold((), move |()| async move {
+					futures_timer::Delay::new(Duration::from_millis(millis)).await;
+					Some((EngineCommand::SealNewBlock {
+						create_empty: true,
+						finalize: false,
+						parent_hash: None,
+						sender: None,
+					}, ()))
+				}),
+			)),
+		};
+
+		let authorship_future = sc_consensus_manual_seal::run_manual_seal(
+			Box::new(client.clone()),
+			env,
+			client,
+			transaction_pool.pool().clone(),
+			commands_stream,
+			select_chain,
+			inherent_data_providers,
+		);
+
+		// the manual seal authorship task replaces both AURA and GRANDPA, so
+		// it is considered essential as well.
+		task_manager.spawn_essential_handle().spawn_blocking("manual-seal", authorship_future);
+
+		return Ok(task_manager);
+	}
+
+	let (block_import, grandpa_link) =
+		import_setup.take()
+			.expect("Link Half and Block Import are present for Full Services or setup failed before. qed");
+
 	if role.is_authority() {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			client.clone(),
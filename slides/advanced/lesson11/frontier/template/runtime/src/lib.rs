@@ -33,11 +33,12 @@ use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata, U256, H160, H256};
 use sp_runtime::traits::{
 	BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, NumberFor, Saturating, Verify,
+	Dispatchable, PostDispatchInfoOf,
 };
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, ModuleId, MultiSignature,
+	transaction_validity::{TransactionSource, TransactionValidity, TransactionValidityError},
+	ApplyExtrinsicResult, DispatchResultWithInfo, ModuleId, MultiSignature,
 };
 use sp_std::{prelude::*, marker::PhantomData};
 #[cfg(feature = "std")]
@@ -50,7 +51,7 @@ pub use balances::Call as BalancesCall;
 pub use evm::Account as EVMAccount;
 pub use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{KeyOwnerProofSystem, Randomness, FindAuthor},
+	traits::{KeyOwnerProofSystem, Randomness, FindAuthor, Get},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
 		IdentityFee, Weight,
@@ -60,6 +61,7 @@ pub use frame_support::{
 use ethereum::{Block as EthereumBlock, Transaction as EthereumTransaction, Receipt as EthereumReceipt};
 use frontier_rpc_primitives::{TransactionStatus};
 
+mod weights;
 
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -295,7 +297,27 @@ impl FeeCalculator for FixedGasPrice {
 
 parameter_types! {
 	pub const EVMModuleId: ModuleId = ModuleId(*b"py/evmpa");
-	pub const ChainId: u64 = 42;
+}
+
+/// Ethereum chain id, configured in the genesis of the Ethereum pallet.
+pub struct ChainId;
+impl Get<u64> for ChainId {
+	fn get() -> u64 {
+		<ethereum::Module<Runtime>>::chain_id()
+	}
+}
+
+/// Ethereum block gas limit, configured in the genesis of the Ethereum pallet.
+pub struct BlockGasLimit;
+impl Get<U256> for BlockGasLimit {
+	fn get() -> U256 {
+		<ethereum::Module<Runtime>>::block_gas_limit()
+	}
+}
+
+parameter_types! {
+	pub const AuthorFeeShare: Perbill = Perbill::from_percent(80);
+	pub const TreasuryFeeShare: Perbill = Perbill::from_percent(0);
 }
 
 impl evm::Trait for Runtime {
@@ -304,8 +326,17 @@ impl evm::Trait for Runtime {
 	type ConvertAccountId = HashTruncateConvertAccountId<BlakeTwo256>;
 	type Currency = Balances;
 	type Event = Event;
-	type Precompiles = ();
+	// ERC-20 tokens for the native currency and the assets of `Assets`.
+	type Precompiles = (erc20::NativeErc20<Runtime>, erc20::AssetsErc20<Runtime>);
 	type ChainId = ChainId;
+	type BlockGasLimit = BlockGasLimit;
+	type FindAuthor = EthereumFindAuthor<Aura>;
+	// Block authors get 80% of EVM fees, and the rest is burnt.
+	type OnChargeTransaction = evm::SplitFee<AuthorFeeShare, TreasuryFeeShare, ()>;
+	type FeePayer = Paymaster;
+	type AccessControl = EVMAccessControl;
+	type EmergencyOrigin = system::EnsureRoot<AccountId>;
+	type WeightInfo = weights::WeightForEvm;
 }
 
 pub struct EthereumFindAuthor<F>(PhantomData<F>);
@@ -324,7 +355,36 @@ impl<F: FindAuthor<u32>> FindAuthor<H160> for EthereumFindAuthor<F>
 
 impl ethereum::Trait for Runtime {
 	type Event = Event;
-	type FindAuthor = EthereumFindAuthor<Aura>;
+	type RuntimeOrigin = Origin;
+	type WeightInfo = weights::WeightForEthereum;
+}
+
+parameter_types! {
+	pub const PaymasterModuleId: ModuleId = ModuleId(*b"py/paymr");
+	// Sponsorship quotas are renewed daily.
+	pub const QuotaPeriod: BlockNumber = DAYS;
+}
+
+impl paymaster::Trait for Runtime {
+	type Event = Event;
+	type PaymasterModuleId = PaymasterModuleId;
+	type QuotaPeriod = QuotaPeriod;
+	type WeightInfo = weights::WeightForPaymaster;
+}
+
+impl assets::Trait for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = u32;
+}
+
+impl erc20::Trait for Runtime {
+	type AddressMapping = evm::HashedAddressMapping<BlakeTwo256>;
+}
+
+impl evm_access_control::Trait for Runtime {
+	type Event = Event;
+	type AdminOrigin = system::EnsureRoot<AccountId>;
 }
 
 construct_runtime!(
@@ -341,11 +401,62 @@ construct_runtime!(
 		Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
 		TransactionPayment: transaction_payment::{Module, Storage},
 		Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
-		Ethereum: ethereum::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+		Ethereum: ethereum::{Module, Call, Storage, Config, Event, Origin},
 		EVM: evm::{Module, Config, Call, Storage, Event<T>},
+		Paymaster: paymaster::{Module, Call, Storage, Event<T>},
+		Assets: assets::{Module, Call, Storage, Event<T>},
+		Erc20: erc20::{Module, Storage},
+		EVMAccessControl: evm_access_control::{Module, Call, Config, Storage, Event},
 	}
 );
 
+impl frontier_self_contained::SelfContainedCall for Call {
+	type SignedInfo = H160;
+
+	fn is_self_contained(&self) -> bool {
+		match self {
+			Call::Ethereum(call) => call.is_self_contained(),
+			_ => false,
+		}
+	}
+
+	fn check_self_contained(&self) -> Option<Result<H160, TransactionValidityError>> {
+		match self {
+			Call::Ethereum(call) => call.check_self_contained(),
+			_ => None,
+		}
+	}
+
+	fn validate_self_contained(&self, info: &H160) -> Option<TransactionValidity> {
+		match self {
+			Call::Ethereum(call) => call.validate_self_contained(info),
+			_ => None,
+		}
+	}
+
+	fn pre_dispatch_self_contained(
+		&self,
+		info: &H160,
+	) -> Option<Result<(), TransactionValidityError>> {
+		match self {
+			Call::Ethereum(call) => call.pre_dispatch_self_contained(info),
+			_ => None,
+		}
+	}
+
+	fn apply_self_contained(
+		self,
+		info: H160,
+	) -> Option<DispatchResultWithInfo<PostDispatchInfoOf<Self>>> {
+		match self {
+			call @ Call::Ethereum(ethereum::Call::transact(..)) => Some(
+				call.dispatch(Origin::from(ethereum::RawOrigin::EthereumTransaction(info)))
+			),
+			_ => None,
+		}
+	}
+}
+
 pub struct TransactionConverter;
 
 impl frontier_rpc_primitives::ConvertTransaction<UncheckedExtrinsic> for TransactionConverter {
@@ -382,10 +493,13 @@ pub type SignedExtra = (
 	system::CheckWeight<Runtime>,
 	transaction_payment::ChargeTransactionPayment<Runtime>,
 );
-/// Unchecked extrinsic type as expected by this runtime.
-pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
+/// Unchecked extrinsic type as expected by this runtime, where Ethereum transactions are
+/// self-contained calls.
+pub type UncheckedExtrinsic =
+	frontier_self_contained::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
-pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+pub type CheckedExtrinsic =
+	frontier_self_contained::CheckedExtrinsic<AccountId, Call, SignedExtra, H160>;
 /// Executive: handles dispatch to the various modules.
 pub type Executive =
 	frame_executive::Executive<Runtime, Block, system::ChainContext<Runtime>, Runtime, AllModules>;
@@ -491,7 +605,11 @@ impl_runtime_apis! {
 		fn storage_at(address: H160, index: U256) -> H256 {
 			let mut tmp = [0u8; 32];
 			index.to_big_endian(&mut tmp);
-			evm::Module::<Runtime>::account_storages(address, H256::from_slice(&tmp[..]))
+			evm::Module::<Runtime>::account_storages(&address, &H256::from_slice(&tmp[..]))
+		}
+
+		fn account_proof(address: H160, storage_keys: Vec<H256>) -> Option<evm::AccountProof> {
+			evm::Module::<Runtime>::account_proof(&address, &storage_keys)
 		}
 
 		fn call(
@@ -508,11 +626,27 @@ impl_runtime_apis! {
 				to,
 				data,
 				value,
-				gas_limit.low_u32(),
+				ethereum::saturated_gas_limit(gas_limit),
 				gas_price,
 				nonce,
 				false,
-			).ok().map(|(_, ret, gas)| (ret, gas))
+			).ok().map(|info| (info.value, info.used_gas))
+		}
+
+		fn fee_payer(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			gas_limit: U256,
+			gas_price: U256,
+		) -> H160 {
+			evm::Module::<Runtime>::fee_payer(
+				from,
+				to,
+				&data,
+				ethereum::saturated_gas_limit(gas_limit),
+				gas_price,
+			)
 		}
 
 		fn block_by_number(number: u32) -> (
@@ -635,4 +769,41 @@ impl_runtime_apis! {
 			None
 		}
 	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	impl frame_benchmarking::Benchmark<Block> for Runtime {
+		fn dispatch_benchmark(
+			pallet: Vec<u8>,
+			benchmark: Vec<u8>,
+			lowest_range_values: Vec<u32>,
+			highest_range_values: Vec<u32>,
+			steps: Vec<u32>,
+			repeat: u32,
+		) -> Result<Vec<frame_benchmarking::BenchmarkBatch>, sp_runtime::RuntimeString> {
+			use frame_benchmarking::{Benchmarking, BenchmarkBatch, add_benchmark};
+
+			let whitelist: Vec<Vec<u8>> = vec![
+				// Block Number
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef702a5c1b19ab7a04f536c519aca4983ac").to_vec(),
+				// Total Issuance
+				hex_literal::hex!("c2261276cc9d1f8598ea4b6a74b15c2f57c875e4cff74148e4628f264b974c80").to_vec(),
+				// Execution Phase
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef7ff553b5a9862a516939d82b3d3d8661a").to_vec(),
+				// Event Count
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef70a98fdbe9ce6c55837576c60c7af3850").to_vec(),
+				// System Events
+				hex_literal::hex!("26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7").to_vec(),
+			];
+
+			let mut batches = Vec::<BenchmarkBatch>::new();
+			let params = (&pallet, &benchmark, &lowest_range_values, &highest_range_values, &steps, repeat, &whitelist);
+
+			add_benchmark!(params, batches, evm, EVM);
+			add_benchmark!(params, batches, ethereum, Ethereum);
+			add_benchmark!(params, batches, paymaster, Paymaster);
+
+			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
+			Ok(batches)
+		}
+	}
 }
@@ -0,0 +1,183 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Estimated weights of the EVM, Ethereum and paymaster pallets.
+//!
+//! These are hand-written estimates, not benchmark results: no reference machine has run
+//! the benchmarks yet. Replace this file with the benchmark output, run on reference
+//! hardware with a node built with the `runtime-benchmarks` feature:
+//!
+//! ```text
+//! frontier-template-node benchmark --chain dev --execution wasm --wasm-execution compiled \
+//!     --pallet evm --extrinsic '*' --steps 50 --repeat 20 --output
+//! frontier-template-node benchmark --chain dev --execution wasm --wasm-execution compiled \
+//!     --pallet ethereum --extrinsic '*' --steps 50 --repeat 20 --output
+//! frontier-template-node benchmark --chain dev --execution wasm --wasm-execution compiled \
+//!     --pallet paymaster --extrinsic '*' --steps 50 --repeat 20 --output
+//! ```
+
+use frame_support::weights::{Weight, constants::RocksDbWeight as DbWeight};
+
+pub struct WeightForEvm;
+impl evm::WeightInfo for WeightForEvm {
+	fn deposit_balance() -> Weight {
+		(62_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn withdraw_balance() -> Weight {
+		(64_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn call(g: u32, ) -> Weight {
+		(95_000_000 as Weight)
+			.saturating_add((25_000 as Weight).saturating_mul(g as Weight))
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn create(g: u32, ) -> Weight {
+		(110_000_000 as Weight)
+			.saturating_add((25_000 as Weight).saturating_mul(g as Weight))
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn create2(g: u32, ) -> Weight {
+		(112_000_000 as Weight)
+			.saturating_add((25_000 as Weight).saturating_mul(g as Weight))
+			.saturating_add(DbWeight::get().reads(9 as Weight))
+			.saturating_add(DbWeight::get().writes(4 as Weight))
+	}
+	fn freeze_contract(c: u32, ) -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn unfreeze_contract(c: u32, ) -> Weight {
+		(30_000_000 as Weight)
+			.saturating_add((2_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(3 as Weight))
+	}
+	fn set_account_code(c: u32, ) -> Weight {
+		(28_000_000 as Weight)
+			.saturating_add((3_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn set_account_storage(s: u32, ) -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add((9_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(s as Weight)))
+	}
+	fn migrate_account() -> Weight {
+		(45_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn commit_state(a: u32, s: u32, ) -> Weight {
+		(12_000_000 as Weight)
+			.saturating_add((30_000_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add((25_000_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().reads((6 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(DbWeight::get().reads((5 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+			.saturating_add(DbWeight::get().writes((6 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(DbWeight::get().writes((5 as Weight).saturating_mul(s as Weight)))
+	}
+}
+
+pub struct WeightForEthereum;
+impl ethereum::WeightInfo for WeightForEthereum {
+	fn recover_signer() -> Weight {
+		(48_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(0 as Weight))
+			.saturating_add(DbWeight::get().writes(0 as Weight))
+	}
+	fn transact(g: u32, ) -> Weight {
+		(140_000_000 as Weight)
+			.saturating_add((25_000 as Weight).saturating_mul(g as Weight))
+			.saturating_add(DbWeight::get().reads(12 as Weight))
+			.saturating_add(DbWeight::get().writes(6 as Weight))
+	}
+	fn on_finalize(t: u32, l: u32, ) -> Weight {
+		(40_000_000 as Weight)
+			.saturating_add((45_000_000 as Weight).saturating_mul(t as Weight))
+			.saturating_add((1_500_000 as Weight).saturating_mul(l as Weight))
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(t as Weight)))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(t as Weight)))
+	}
+}
+
+pub struct WeightForPaymaster;
+impl paymaster::WeightInfo for WeightForPaymaster {
+	fn register() -> Weight {
+		(35_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn force_register() -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(2 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn unregister() -> Weight {
+		(60_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(4 as Weight))
+			.saturating_add(DbWeight::get().writes(5 as Weight))
+	}
+	fn set_rules(s: u32, ) -> Weight {
+		(25_000_000 as Weight)
+			.saturating_add((4_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes(1 as Weight))
+	}
+	fn allow_callers(c: u32, ) -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add((3_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	fn disallow_callers(c: u32, ) -> Weight {
+		(22_000_000 as Weight)
+			.saturating_add((3_000_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(c as Weight)))
+	}
+	fn fund() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn defund() -> Weight {
+		(55_000_000 as Weight)
+			.saturating_add(DbWeight::get().reads(3 as Weight))
+			.saturating_add(DbWeight::get().writes(2 as Weight))
+	}
+	fn on_initialize(r: u32, ) -> Weight {
+		(5_000_000 as Weight)
+			.saturating_add((2_500_000 as Weight).saturating_mul(r as Weight))
+			.saturating_add(DbWeight::get().reads(1 as Weight))
+			.saturating_add(DbWeight::get().reads((1 as Weight).saturating_mul(r as Weight)))
+			.saturating_add(DbWeight::get().writes((1 as Weight).saturating_mul(r as Weight)))
+	}
+}
@@ -0,0 +1,207 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Offline state transition tool, running Ethereum transactions through the EVM used by
+//! Frontier. Inputs, outputs and exit codes follow geth's `evm t8n`, so results can be
+//! diffed against geth.
+
+mod transition;
+mod types;
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use ethereum_types::U256;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use structopt::StructOpt;
+use transition::{Fork, Transition};
+use types::{Alloc, Env, Input, Transaction};
+
+/// Exit code of invalid `--state.*` parameters.
+const ERROR_CONFIG: i32 = 3;
+/// Exit code of malformed input files.
+const ERROR_JSON: i32 = 10;
+/// Exit code of unreadable inputs or unwritable outputs.
+const ERROR_IO: i32 = 11;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "evm-t8n", about = "Executes a full state transition with the Frontier EVM")]
+struct Cli {
+	/// `stdin` or file name of where to find the prestate alloc to use.
+	#[structopt(long = "input.alloc", default_value = "alloc.json")]
+	input_alloc: String,
+
+	/// `stdin` or file name of where to find the prestate env to use.
+	#[structopt(long = "input.env", default_value = "env.json")]
+	input_env: String,
+
+	/// `stdin` or file name of where to find the transactions to apply.
+	#[structopt(long = "input.txs", default_value = "txs.json")]
+	input_txs: String,
+
+	/// Directory the output files are placed in.
+	#[structopt(long = "output.basedir", parse(from_os_str))]
+	output_basedir: Option<PathBuf>,
+
+	/// Where to put the post-state alloc: `stdout`, `stderr` or a file name.
+	#[structopt(long = "output.alloc", default_value = "alloc.json")]
+	output_alloc: String,
+
+	/// Where to put the execution result: `stdout`, `stderr` or a file name.
+	#[structopt(long = "output.result", default_value = "result.json")]
+	output_result: String,
+
+	/// Fork rules to use, `Frontier` or `Istanbul`.
+	#[structopt(long = "state.fork", default_value = "Istanbul")]
+	fork: String,
+
+	/// Chain id transactions are signed for.
+	#[structopt(long = "state.chainid", default_value = "1")]
+	chain_id: u64,
+
+	/// Mining reward, in wei. Set to -1 to disable.
+	#[structopt(long = "state.reward", default_value = "0", allow_hyphen_values = true)]
+	reward: i64,
+}
+
+struct Error {
+	code: i32,
+	message: String,
+}
+
+impl Error {
+	fn new(code: i32, message: String) -> Self {
+		Self { code, message }
+	}
+}
+
+fn read_json<T: DeserializeOwned>(path: &str) -> Result<T, Error> {
+	let file = File::open(path)
+		.map_err(|e| Error::new(ERROR_IO, format!("failed reading {}: {}", path, e)))?;
+	serde_json::from_reader(io::BufReader::new(file))
+		.map_err(|e| Error::new(ERROR_JSON, format!("failed unmarshaling {}: {}", path, e)))
+}
+
+/// Reads an input either from its own file, or from the object read from stdin.
+fn input<T: DeserializeOwned>(path: &str, name: &str, stdin: &mut Option<Input>) -> Result<T, Error>
+	where Input: Take<T>
+{
+	if path != "stdin" {
+		return read_json(path)
+	}
+	stdin.as_mut()
+		.and_then(|input| input.take())
+		.ok_or_else(|| Error::new(ERROR_JSON, format!("`{}` missing from stdin input", name)))
+}
+
+trait Take<T> {
+	fn take(&mut self) -> Option<T>;
+}
+
+impl Take<Alloc> for Input {
+	fn take(&mut self) -> Option<Alloc> { self.alloc.take() }
+}
+
+impl Take<Env> for Input {
+	fn take(&mut self) -> Option<Env> { self.env.take() }
+}
+
+impl Take<Vec<Transaction>> for Input {
+	fn take(&mut self) -> Option<Vec<Transaction>> { self.txs.take() }
+}
+
+/// Writes `value` to a file, or adds it to the object printed on stdout or stderr.
+fn output<T: Serialize>(
+	cli: &Cli,
+	destination: &str,
+	name: &str,
+	value: &T,
+	stdout: &mut Map<String, Value>,
+	stderr: &mut Map<String, Value>,
+) -> Result<(), Error> {
+	let json_error = |e: serde_json::Error| Error::new(ERROR_JSON, format!("failed marshaling output: {}", e));
+	match destination {
+		"stdout" => { stdout.insert(name.into(), serde_json::to_value(value).map_err(json_error)?); },
+		"stderr" => { stderr.insert(name.into(), serde_json::to_value(value).map_err(json_error)?); },
+		path => {
+			let path = match &cli.output_basedir {
+				Some(basedir) => basedir.join(path),
+				None => PathBuf::from(path),
+			};
+			let json = serde_json::to_string_pretty(value).map_err(json_error)?;
+			std::fs::write(&path, json)
+				.map_err(|e| Error::new(ERROR_IO, format!("failed writing output {}: {}", path.display(), e)))?;
+		},
+	}
+	Ok(())
+}
+
+fn run(cli: Cli) -> Result<(), Error> {
+	let fork: Fork = cli.fork.parse().map_err(|e| Error::new(ERROR_CONFIG, e))?;
+	let reward = match cli.reward {
+		reward if reward < 0 => None,
+		reward => Some(U256::from(reward)),
+	};
+
+	let mut stdin = if [&cli.input_alloc, &cli.input_env, &cli.input_txs].iter().any(|path| *path == "stdin") {
+		let mut json = String::new();
+		io::stdin().read_to_string(&mut json)
+			.map_err(|e| Error::new(ERROR_IO, format!("failed reading stdin: {}", e)))?;
+		Some(serde_json::from_str::<Input>(&json)
+			.map_err(|e| Error::new(ERROR_JSON, format!("failed unmarshaling stdin: {}", e)))?)
+	} else {
+		None
+	};
+	let alloc: Alloc = input(&cli.input_alloc, "alloc", &mut stdin)?;
+	let env: Env = input(&cli.input_env, "env", &mut stdin)?;
+	let txs: Vec<Transaction> = input(&cli.input_txs, "txs", &mut stdin)?;
+
+	if env.current_gas_limit.0 > U256::from(u64::max_value()) {
+		return Err(Error::new(ERROR_JSON, "currentGasLimit does not fit in 64 bits".into()))
+	}
+
+	let mut transition = Transition::new(fork, cli.chain_id, &env, alloc);
+	for (index, tx) in txs.into_iter().enumerate() {
+		transition.apply(index, tx);
+	}
+	let (alloc, result) = transition.finalize(reward, &env);
+	for rejected in &result.rejected {
+		eprintln!("{}", rejected);
+	}
+
+	let mut stdout = Map::new();
+	let mut stderr = Map::new();
+	output(&cli, &cli.output_alloc, "alloc", &alloc, &mut stdout, &mut stderr)?;
+	output(&cli, &cli.output_result, "result", &result, &mut stdout, &mut stderr)?;
+	if !stdout.is_empty() {
+		println!("{}", serde_json::to_string_pretty(&stdout).expect("serializing a JSON map cannot fail; qed"));
+	}
+	if !stderr.is_empty() {
+		eprintln!("{}", serde_json::to_string_pretty(&stderr).expect("serializing a JSON map cannot fail; qed"));
+	}
+
+	Ok(())
+}
+
+fn main() {
+	if let Err(e) = run(Cli::from_args()) {
+		eprintln!("ERROR({}): {}", e.code, e.message);
+		std::process::exit(e.code);
+	}
+}
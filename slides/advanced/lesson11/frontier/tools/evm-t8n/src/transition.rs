@@ -0,0 +1,529 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Applies a list of signed transactions on top of a pre-state with `StackExecutor`.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use ethereum::{TransactionAction, TransactionSignature};
+use ethereum_types::{Bloom, BloomInput, H160, H256, U256};
+use evm::{Config, CreateScheme};
+use evm::backend::{ApplyBackend, MemoryAccount, MemoryBackend, MemoryVicinity};
+use evm::executor::StackExecutor;
+use rlp::{Encodable, RlpStream};
+use sha3::{Digest, Keccak256};
+use trie_memory::{AnyMemoryTrieMut, FixedSecureMemoryTrieMut};
+use crate::types::{
+	Account, Address, Alloc, Bytes, Env, ExecutionResult, Log, LogsBloom, Quantity, Receipt,
+	Rejected, Transaction, Word,
+};
+
+static FRONTIER_CONFIG: Config = Config::frontier();
+static ISTANBUL_CONFIG: Config = Config::istanbul();
+
+/// Hard forks supported by the EVM.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fork {
+	Frontier,
+	Istanbul,
+}
+
+impl FromStr for Fork {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"Frontier" => Ok(Self::Frontier),
+			"Istanbul" => Ok(Self::Istanbul),
+			_ => Err(format!("unsupported fork {}, expected `Frontier` or `Istanbul`", s)),
+		}
+	}
+}
+
+impl Fork {
+	fn config(&self) -> &'static Config {
+		match self {
+			Self::Frontier => &FRONTIER_CONFIG,
+			Self::Istanbul => &ISTANBUL_CONFIG,
+		}
+	}
+
+	/// Homestead and later forks reject signatures with a high `s` value.
+	fn requires_low_s(&self) -> bool {
+		*self != Self::Frontier
+	}
+
+	/// EIP-155 replay protected signatures are accepted from Spurious Dragon.
+	fn has_replay_protection(&self) -> bool {
+		*self != Self::Frontier
+	}
+
+	/// Receipts carry a status instead of an intermediate state root from Byzantium.
+	fn has_receipt_status(&self) -> bool {
+		*self != Self::Frontier
+	}
+}
+
+fn keccak_256(data: &[u8]) -> H256 {
+	H256::from_slice(Keccak256::digest(data).as_slice())
+}
+
+/// Root of the secure account trie, as committed to by Ethereum block headers.
+pub fn state_root(state: &BTreeMap<H160, MemoryAccount>) -> H256 {
+	let mut accounts = FixedSecureMemoryTrieMut::<H160, ethereum::Account>::default();
+	for (address, account) in state {
+		let mut storage = FixedSecureMemoryTrieMut::<H256, U256>::default();
+		for (index, value) in &account.storage {
+			if *value != H256::default() {
+				storage.insert(index, &U256::from_big_endian(value.as_bytes()));
+			}
+		}
+
+		accounts.insert(address, &ethereum::Account {
+			nonce: account.nonce,
+			balance: account.balance,
+			storage_root: storage.root(),
+			code_hash: keccak_256(&account.code),
+		});
+	}
+	accounts.root()
+}
+
+/// Root of a trie keyed by the RLP encoded index of each item.
+fn ordered_root<T: Encodable>(items: &[T]) -> H256 {
+	let mut trie = AnyMemoryTrieMut::default();
+	for (index, item) in items.iter().enumerate() {
+		trie.insert(&index, item);
+	}
+	trie.root()
+}
+
+/// Consensus encoding of a receipt.
+struct ReceiptRlp {
+	root: Option<H256>,
+	succeeded: bool,
+	cumulative_gas_used: U256,
+	logs_bloom: Bloom,
+	logs: Vec<ethereum::Log>,
+}
+
+impl Encodable for ReceiptRlp {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(4);
+		match self.root {
+			Some(root) => s.append(&root),
+			None => s.append(&(self.succeeded as u8)),
+		};
+		s.append(&self.cumulative_gas_used);
+		s.append(&self.logs_bloom);
+		s.append_list(&self.logs);
+	}
+}
+
+fn recover_signer(
+	transaction: &ethereum::Transaction,
+	fork: Fork,
+	chain_id: u64,
+) -> Result<H160, String> {
+	let signature = &transaction.signature;
+	match signature.chain_id() {
+		Some(_) if !fork.has_replay_protection() =>
+			return Err("replay protected transaction before EIP-155".into()),
+		Some(id) if id != chain_id =>
+			return Err(format!("invalid chain id: have {}, want {}", id, chain_id)),
+		_ => (),
+	}
+	if fork.requires_low_s() && !signature.is_low_s() {
+		return Err("invalid transaction v, r, s values".into())
+	}
+
+	let mut sig = [0u8; 64];
+	sig[0..32].copy_from_slice(&signature.r()[..]);
+	sig[32..64].copy_from_slice(&signature.s()[..]);
+	let message = secp256k1::Message::parse(&transaction.message_hash(signature.chain_id()).0);
+	let public = secp256k1::RecoveryId::parse(signature.standard_v())
+		.and_then(|recovery_id| {
+			secp256k1::recover(&message, &secp256k1::Signature::parse(&sig), &recovery_id)
+		})
+		.map_err(|e| format!("invalid signature: {:?}", e))?;
+
+	Ok(H160::from(keccak_256(&public.serialize()[1..])))
+}
+
+fn to_signed_transaction(transaction: Transaction) -> Result<ethereum::Transaction, String> {
+	let mut r = [0u8; 32];
+	let mut s = [0u8; 32];
+	transaction.r.0.to_big_endian(&mut r);
+	transaction.s.0.to_big_endian(&mut s);
+	let signature = if transaction.v.0 > U256::from(u64::max_value()) {
+		None
+	} else {
+		TransactionSignature::new(transaction.v.0.low_u64(), H256::from(r), H256::from(s))
+	}.ok_or_else(|| String::from("invalid transaction v, r, s values"))?;
+
+	Ok(ethereum::Transaction {
+		nonce: transaction.nonce.0,
+		gas_price: transaction.gas_price.0,
+		gas_limit: transaction.gas.0,
+		action: match transaction.to {
+			Some(Address(to)) => TransactionAction::Call(to),
+			None => TransactionAction::Create,
+		},
+		value: transaction.value.0,
+		signature,
+		input: transaction.input.0,
+	})
+}
+
+fn intrinsic_gas(transaction: &ethereum::Transaction, config: &Config) -> U256 {
+	let base = match transaction.action {
+		TransactionAction::Call(_) => config.gas_transaction_call,
+		TransactionAction::Create => config.gas_transaction_create,
+	};
+	let zeros = transaction.input.iter().filter(|b| **b == 0).count();
+	let non_zeros = transaction.input.len() - zeros;
+
+	U256::from(base) +
+		U256::from(zeros) * U256::from(config.gas_transaction_zero_data) +
+		U256::from(non_zeros) * U256::from(config.gas_transaction_non_zero_data)
+}
+
+fn logs_bloom(logs: &[ethereum::Log]) -> Bloom {
+	let mut bloom = Bloom::default();
+	for log in logs {
+		bloom.accrue(BloomInput::Raw(&log.address[..]));
+		for topic in &log.topics {
+			bloom.accrue(BloomInput::Raw(&topic[..]));
+		}
+	}
+	bloom
+}
+
+/// Block being built on top of a pre-state.
+pub struct Transition {
+	fork: Fork,
+	chain_id: u64,
+	vicinity: MemoryVicinity,
+	state: BTreeMap<H160, MemoryAccount>,
+	gas_pool: U256,
+	cumulative_gas_used: U256,
+	transactions: Vec<ethereum::Transaction>,
+	receipts: Vec<Receipt>,
+	receipts_rlp: Vec<ReceiptRlp>,
+	logs: Vec<ethereum::Log>,
+	rejected: Vec<Rejected>,
+}
+
+impl Transition {
+	pub fn new(fork: Fork, chain_id: u64, env: &Env, alloc: Alloc) -> Self {
+		let number = env.current_number.0;
+		// `block_hashes[i]` is the hash of block `number - i - 1`.
+		let block_hashes = (0..std::cmp::min(number, U256::from(256)).as_u64())
+			.map(|i| {
+				let ancestor = Quantity(number - U256::from(i) - U256::one());
+				env.block_hashes.get(&ancestor).map(|hash| hash.0).unwrap_or_default()
+			})
+			.collect();
+
+		let state = alloc.into_iter()
+			.map(|(Address(address), account)| (address, MemoryAccount {
+				nonce: account.nonce.0,
+				balance: account.balance.0,
+				storage: account.storage.into_iter()
+					.map(|(index, value)| (index.0, value.0))
+					.collect(),
+				code: account.code.0,
+			}))
+			.collect();
+
+		Self {
+			fork,
+			chain_id,
+			vicinity: MemoryVicinity {
+				gas_price: U256::zero(),
+				origin: H160::default(),
+				chain_id: U256::from(chain_id),
+				block_hashes,
+				block_number: number,
+				block_coinbase: env.current_coinbase.0,
+				block_timestamp: env.current_timestamp.0,
+				block_difficulty: env.current_difficulty.0,
+				block_gas_limit: env.current_gas_limit.0,
+			},
+			state,
+			gas_pool: env.current_gas_limit.0,
+			cumulative_gas_used: U256::zero(),
+			transactions: Vec::new(),
+			receipts: Vec::new(),
+			receipts_rlp: Vec::new(),
+			logs: Vec::new(),
+			rejected: Vec::new(),
+		}
+	}
+
+	fn delete_empty(&self) -> bool {
+		!self.fork.config().empty_considered_exists
+	}
+
+	/// Applies the transaction at `index` of `txs.json`, rejecting it if it cannot be
+	/// included in the block.
+	pub fn apply(&mut self, index: usize, transaction: Transaction) {
+		if let Err(error) = self.try_apply(transaction) {
+			self.rejected.push(Rejected { index, error });
+		}
+	}
+
+	fn try_apply(&mut self, transaction: Transaction) -> Result<(), String> {
+		let transaction = to_signed_transaction(transaction)?;
+		let source = recover_signer(&transaction, self.fork, self.chain_id)?;
+		let config = self.fork.config();
+
+		let account = self.state.get(&source).cloned().unwrap_or_default();
+		if transaction.nonce < account.nonce {
+			return Err(format!(
+				"nonce too low: address {:?}, tx: {} state: {}",
+				source, transaction.nonce, account.nonce,
+			))
+		}
+		if transaction.nonce > account.nonce {
+			return Err(format!(
+				"nonce too high: address {:?}, tx: {} state: {}",
+				source, transaction.nonce, account.nonce,
+			))
+		}
+		if transaction.gas_limit > self.gas_pool {
+			return Err("gas limit reached".into())
+		}
+		// The executor counts gas in `usize`, which can be narrower than the block gas limit.
+		if transaction.gas_limit > U256::from(usize::max_value()) {
+			return Err("gas limit too high".into())
+		}
+		let total_fee = transaction.gas_limit.checked_mul(transaction.gas_price)
+			.ok_or_else(|| String::from("fee overflow"))?;
+		let total_payment = total_fee.checked_add(transaction.value)
+			.ok_or_else(|| String::from("payment overflow"))?;
+		if account.balance < total_payment {
+			return Err(format!(
+				"insufficient funds for gas * price + value: address {:?} have {} want {}",
+				source, account.balance, total_payment,
+			))
+		}
+		if transaction.gas_limit < intrinsic_gas(&transaction, config) {
+			return Err("intrinsic gas too low".into())
+		}
+
+		let transaction_hash = keccak_256(&rlp::encode(&transaction));
+		let gas_limit = transaction.gas_limit.as_usize();
+		self.vicinity.gas_price = transaction.gas_price;
+		self.vicinity.origin = source;
+		let delete_empty = self.delete_empty();
+
+		let mut backend = MemoryBackend::new(&self.vicinity, std::mem::take(&mut self.state));
+		let mut executor = StackExecutor::new(&backend, gas_limit, config);
+		executor.withdraw(source, total_fee).map_err(|e| format!("{:?}", e))?;
+
+		let (reason, contract_address) = match transaction.action {
+			TransactionAction::Call(target) => (executor.transact_call(
+				source,
+				target,
+				transaction.value,
+				transaction.input.clone(),
+				gas_limit,
+			).0, None),
+			TransactionAction::Create => {
+				let address = executor.create_address(CreateScheme::Legacy { caller: source });
+				(executor.transact_create(
+					source,
+					transaction.value,
+					transaction.input.clone(),
+					gas_limit,
+				), Some(address))
+			},
+		};
+
+		let used_gas = U256::from(executor.used_gas());
+		let actual_fee = executor.fee(transaction.gas_price);
+		executor.deposit(source, total_fee - actual_fee);
+		executor.deposit(self.vicinity.block_coinbase, actual_fee);
+
+		let (values, logs) = executor.deconstruct();
+		// Logs of failed transactions are not part of the block.
+		let logs = logs.into_iter()
+			.filter(|_| reason.is_succeed())
+			.collect::<Vec<_>>();
+		backend.apply(values, logs.clone(), delete_empty);
+		self.state = backend.state().clone();
+
+		let logs = logs.into_iter()
+			.map(|log| ethereum::Log { address: log.address, topics: log.topics, data: log.data })
+			.collect::<Vec<_>>();
+		self.gas_pool -= used_gas;
+		self.cumulative_gas_used += used_gas;
+
+		let transaction_index = Quantity(U256::from(self.transactions.len()));
+		let bloom = logs_bloom(&logs);
+		let root = if self.fork.has_receipt_status() {
+			None
+		} else {
+			Some(state_root(&self.state))
+		};
+		self.receipts.push(Receipt {
+			root: Bytes(root.map(|root| root.as_bytes().to_vec()).unwrap_or_default()),
+			status: Quantity(U256::from(reason.is_succeed() as u8)),
+			cumulative_gas_used: Quantity(self.cumulative_gas_used),
+			logs_bloom: LogsBloom(bloom),
+			logs: if logs.is_empty() {
+				None
+			} else {
+				Some(logs.iter().enumerate().map(|(i, log)| Log {
+					address: Address(log.address),
+					topics: log.topics.iter().cloned().map(Word).collect(),
+					data: Bytes(log.data.clone()),
+					block_number: Quantity(self.vicinity.block_number),
+					transaction_hash: Word(transaction_hash),
+					transaction_index,
+					block_hash: Word::default(),
+					log_index: Quantity(U256::from(self.logs.len() + i)),
+					removed: false,
+				}).collect())
+			},
+			transaction_hash: Word(transaction_hash),
+			contract_address: Address(contract_address.unwrap_or_default()),
+			gas_used: Quantity(used_gas),
+			block_hash: Word::default(),
+			transaction_index,
+		});
+		self.receipts_rlp.push(ReceiptRlp {
+			root,
+			succeeded: reason.is_succeed(),
+			cumulative_gas_used: self.cumulative_gas_used,
+			logs_bloom: bloom,
+			logs: logs.clone(),
+		});
+		self.logs.extend(logs);
+		self.transactions.push(transaction);
+
+		Ok(())
+	}
+
+	/// Adds `amount` to the balance of `address`, which counts as touching the account.
+	fn credit(&mut self, address: H160, amount: U256) {
+		let delete_empty = self.delete_empty();
+		let account = self.state.entry(address).or_default();
+		account.balance = account.balance.saturating_add(amount);
+
+		if delete_empty && account.balance.is_zero() && account.nonce.is_zero() && account.code.is_empty() {
+			self.state.remove(&address);
+		}
+	}
+
+	/// Pays the block and ommer rewards and returns the post-state and the execution result.
+	pub fn finalize(mut self, reward: Option<U256>, env: &Env) -> (Alloc, ExecutionResult) {
+		if let Some(reward) = reward {
+			let mut miner_reward = reward;
+			for ommer in &env.ommers {
+				let delta = U256::from(8u64.saturating_sub(ommer.delta));
+				self.credit(ommer.address.0, delta * reward / 8);
+				miner_reward = miner_reward.saturating_add(reward / 32);
+			}
+			self.credit(self.vicinity.block_coinbase, miner_reward);
+		}
+
+		let mut bloom = Bloom::default();
+		for receipt in &self.receipts_rlp {
+			bloom.accrue_bloom(&receipt.logs_bloom);
+		}
+
+		let result = ExecutionResult {
+			state_root: Word(state_root(&self.state)),
+			tx_root: Word(ordered_root(&self.transactions)),
+			receipts_root: Word(ordered_root(&self.receipts_rlp)),
+			logs_hash: Word(keccak_256(&rlp::encode_list(&self.logs))),
+			logs_bloom: LogsBloom(bloom),
+			receipts: self.receipts,
+			rejected: self.rejected,
+			gas_used: Quantity(self.cumulative_gas_used),
+		};
+
+		let alloc = self.state.into_iter()
+			.map(|(address, account)| (Address(address), Account {
+				code: Bytes(account.code),
+				storage: account.storage.into_iter()
+					.map(|(index, value)| (Word(index), Word(value)))
+					.collect(),
+				balance: Quantity(account.balance),
+				nonce: Quantity(account.nonce),
+			}))
+			.collect();
+
+		(alloc, result)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::Value;
+
+	// `testdata/1` applies the EIP-155 example transaction, the same transaction again, which
+	// is rejected, and a call to a contract emitting a log. `exp.json` is laid out like the
+	// `exp.json` files of geth's `evm t8n` test data.
+	fn fixture<T: serde::de::DeserializeOwned>(json: &str) -> T {
+		serde_json::from_str(json).unwrap()
+	}
+
+	#[test]
+	fn block_matches_expected_result() {
+		let alloc: Alloc = fixture(include_str!("../testdata/1/alloc.json"));
+		let env: Env = fixture(include_str!("../testdata/1/env.json"));
+		let txs: Vec<Transaction> = fixture(include_str!("../testdata/1/txs.json"));
+		let expected: Value = fixture(include_str!("../testdata/1/exp.json"));
+
+		let mut transition = Transition::new(Fork::Istanbul, 1, &env, alloc);
+		for (index, tx) in txs.into_iter().enumerate() {
+			transition.apply(index, tx);
+		}
+		let (alloc, result) = transition.finalize(None, &env);
+		let result = serde_json::to_value(&result).unwrap();
+
+		assert_eq!(serde_json::to_value(&alloc).unwrap(), expected["alloc"]);
+		for key in &["stateRoot", "txRoot", "receiptsRoot", "logsHash", "logsBloom", "receipts", "gasUsed"] {
+			assert_eq!(result[key], expected["result"][key], "{}", key);
+		}
+
+		let rejected = result["rejected"].as_array().unwrap();
+		assert_eq!(rejected.len(), 1);
+		assert_eq!(rejected[0]["index"], expected["result"]["rejected"][0]["index"]);
+		assert!(rejected[0]["error"].as_str().unwrap().starts_with("nonce too low"));
+	}
+
+	#[test]
+	fn gas_limit_above_usize_is_rejected() {
+		let mut env: Env = fixture(include_str!("../testdata/1/env.json"));
+		env.current_gas_limit = Quantity(U256::max_value());
+		let mut txs: Vec<Transaction> = fixture(include_str!("../testdata/1/txs.json"));
+		let mut transaction = txs.remove(0);
+		transaction.gas = Quantity(U256::from(usize::max_value()) + 1);
+
+		let mut transition = Transition::new(Fork::Istanbul, 1, &env, Alloc::new());
+		transition.apply(0, transaction);
+		let (_, result) = transition.finalize(None, &env);
+
+		assert_eq!(result.rejected.len(), 1);
+		assert!(result.receipts.is_empty());
+	}
+}
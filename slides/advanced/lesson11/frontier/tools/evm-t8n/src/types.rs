@@ -0,0 +1,274 @@
+// This file is part of Frontier.
+
+// Copyright (C) 2019-2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Input and output files, in the format used by geth's `evm t8n`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use ethereum_types::{Bloom, H160, H256, U256};
+use rustc_hex::{FromHex, ToHex};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+fn strip_hex_prefix(s: &str) -> Option<&str> {
+	if s.starts_with("0x") || s.starts_with("0X") {
+		Some(&s[2..])
+	} else {
+		None
+	}
+}
+
+/// Parses a quantity the way geth does: `0x`-prefixed hex, otherwise decimal.
+fn parse_quantity(s: &str) -> Result<U256, String> {
+	match strip_hex_prefix(s) {
+		Some("") => Ok(U256::zero()),
+		Some(hex) if hex.len() > 64 => Err(format!("Invalid quantity {}: more than 256 bits", s)),
+		Some(hex) => U256::from_str(hex).map_err(|e| format!("Invalid quantity {}: {:?}", s, e)),
+		None => U256::from_dec_str(s).map_err(|e| format!("Invalid quantity {}: {:?}", s, e)),
+	}
+}
+
+/// JSON quantities are usually hex strings, but plain numbers are accepted too.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawQuantity {
+	String(String),
+	Number(u64),
+}
+
+/// A 256-bit quantity, serialized as `0x`-prefixed hex without leading zeros.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Quantity(pub U256);
+
+impl Quantity {
+	pub fn is_zero(&self) -> bool {
+		self.0.is_zero()
+	}
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		match RawQuantity::deserialize(deserializer)? {
+			RawQuantity::String(s) => parse_quantity(&s).map(Quantity).map_err(de::Error::custom),
+			RawQuantity::Number(n) => Ok(Quantity(U256::from(n))),
+		}
+	}
+}
+
+impl Serialize for Quantity {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("{:#x}", self.0))
+	}
+}
+
+/// A storage slot or value, left-padded to 32 bytes when parsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Word(pub H256);
+
+impl<'de> Deserialize<'de> for Word {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		let value = parse_quantity(&format!("0x{}", strip_hex_prefix(&s).unwrap_or(&s)))
+			.map_err(de::Error::custom)?;
+		let mut word = [0u8; 32];
+		value.to_big_endian(&mut word);
+		Ok(Word(H256::from(word)))
+	}
+}
+
+impl Serialize for Word {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("{:?}", self.0))
+	}
+}
+
+/// A 20-byte account address.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Address(pub H160);
+
+impl<'de> Deserialize<'de> for Address {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		H160::from_str(strip_hex_prefix(&s).unwrap_or(&s))
+			.map(Address)
+			.map_err(|e| de::Error::custom(format!("Invalid address {}: {:?}", s, e)))
+	}
+}
+
+impl Serialize for Address {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("{:?}", self.0))
+	}
+}
+
+/// Arbitrary binary data, serialized as `0x`-prefixed hex.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+impl Bytes {
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+}
+
+impl<'de> Deserialize<'de> for Bytes {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let s = String::deserialize(deserializer)?;
+		strip_hex_prefix(&s).unwrap_or(&s)
+			.from_hex()
+			.map(Bytes)
+			.map_err(|e| de::Error::custom(format!("Invalid hex data {}: {}", s, e)))
+	}
+}
+
+impl Serialize for Bytes {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("0x{}", self.0.to_hex::<String>()))
+	}
+}
+
+/// Account of the pre-state and post-state allocations.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Account {
+	#[serde(default, skip_serializing_if = "Bytes::is_empty")]
+	pub code: Bytes,
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub storage: BTreeMap<Word, Word>,
+	pub balance: Quantity,
+	#[serde(default, skip_serializing_if = "Quantity::is_zero")]
+	pub nonce: Quantity,
+}
+
+/// State allocation, `alloc.json`.
+pub type Alloc = BTreeMap<Address, Account>;
+
+/// Uncle of the block being built, rewarded along with the coinbase.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Ommer {
+	pub delta: u64,
+	pub address: Address,
+}
+
+/// Block environment, `env.json`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Env {
+	pub current_coinbase: Address,
+	pub current_difficulty: Quantity,
+	pub current_gas_limit: Quantity,
+	pub current_number: Quantity,
+	pub current_timestamp: Quantity,
+	/// Hashes of previous blocks, keyed by block number.
+	#[serde(default)]
+	pub block_hashes: BTreeMap<Quantity, Word>,
+	#[serde(default)]
+	pub ommers: Vec<Ommer>,
+}
+
+/// Signed legacy transaction, as an entry of `txs.json`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+	pub nonce: Quantity,
+	pub gas_price: Quantity,
+	pub gas: Quantity,
+	#[serde(default)]
+	pub to: Option<Address>,
+	pub value: Quantity,
+	pub input: Bytes,
+	pub v: Quantity,
+	pub r: Quantity,
+	pub s: Quantity,
+}
+
+/// All inputs read at once from stdin, when an input file is given as `stdin`.
+#[derive(Deserialize)]
+pub struct Input {
+	pub alloc: Option<Alloc>,
+	pub env: Option<Env>,
+	pub txs: Option<Vec<Transaction>>,
+}
+
+/// A 2048-bit logs bloom.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LogsBloom(pub Bloom);
+
+impl Serialize for LogsBloom {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_str(&format!("0x{}", self.0.as_bytes().to_hex::<String>()))
+	}
+}
+
+/// Log emitted by an included transaction.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+	pub address: Address,
+	pub topics: Vec<Word>,
+	pub data: Bytes,
+	pub block_number: Quantity,
+	pub transaction_hash: Word,
+	pub transaction_index: Quantity,
+	pub block_hash: Word,
+	pub log_index: Quantity,
+	pub removed: bool,
+}
+
+/// Receipt of an included transaction.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+	/// Intermediate state root, only set before Byzantium.
+	pub root: Bytes,
+	pub status: Quantity,
+	pub cumulative_gas_used: Quantity,
+	pub logs_bloom: LogsBloom,
+	pub logs: Option<Vec<Log>>,
+	pub transaction_hash: Word,
+	pub contract_address: Address,
+	pub gas_used: Quantity,
+	pub block_hash: Word,
+	pub transaction_index: Quantity,
+}
+
+/// Transaction left out of the block.
+#[derive(Clone, Debug, Serialize)]
+pub struct Rejected {
+	pub index: usize,
+	pub error: String,
+}
+
+impl fmt::Display for Rejected {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "rejected tx {}: {}", self.index, self.error)
+	}
+}
+
+/// Result of the state transition, `result.json`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionResult {
+	pub state_root: Word,
+	pub tx_root: Word,
+	pub receipts_root: Word,
+	pub logs_hash: Word,
+	pub logs_bloom: LogsBloom,
+	pub receipts: Vec<Receipt>,
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub rejected: Vec<Rejected>,
+	pub gas_used: Quantity,
+}